@@ -5,39 +5,74 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use crate::config::{load_config, save_config};
+use crate::bundle;
+use crate::categories::assigned;
+use crate::config::{load_config, update_config};
+use crate::error::{AppError, Resource};
+use crate::smart_categories::RuleSet;
 
 static APP_CACHE: Lazy<Mutex<Vec<AppInfo>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct AppInfo {
     pub name: String,
     pub path: String,
     pub is_system: bool,
     pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub usage_count: u32,
     pub icon_data: Option<String>,
     pub date_modified: u64,
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+    /// `LSApplicationCategoryType` from the bundle's Info.plist.
+    #[serde(default)]
+    pub app_category_type: Option<String>,
+    #[serde(default)]
+    pub architectures: Vec<String>,
+    #[serde(default)]
+    pub date_installed: u64,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 #[tauri::command]
-pub fn launch_app(path: String) -> Result<(), String> {
+pub fn launch_app(path: String) -> Result<(), AppError> {
     if !Path::new(&path).exists() {
-        return Err("App not found".to_string());
+        return Err(AppError::not_found(Resource::App, path));
     }
 
-    let status = Command::new("open")
-        .arg(&path)
-        .status()
-        .map_err(|e| format!("Failed to execute open: {}", e))?;
+    let status = Command::new("open").arg(&path).status().map_err(|e| {
+        tracing::error!(path = %path, "could not run open: {}", e);
+        AppError::command("open", e)
+    })?;
     if !status.success() {
-        return Err("Failed to launch app".to_string());
+        tracing::warn!(path = %path, %status, "launch failed");
+        return Err(AppError::command("open", status));
     }
 
-    let mut config = load_config();
-    let count = config.usage_counts.entry(path.clone()).or_insert(0);
-    *count += 1;
-    save_config(&config);
+    // The app is already open, so failing to count the launch is not an error.
+    let recorded = update_config(|config| {
+        if !config.incognito {
+            *config.usage_counts.entry(path.clone()).or_insert(0) += 1;
+        }
+        !config.incognito
+    });
+    match recorded {
+        Ok(true) => {
+            tracing::debug!(path = %path, "launched app");
+            if let Err(e) = crate::usage::record_launch(&path) {
+                tracing::warn!(path = %path, "could not record launch: {}", e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!(path = %path, "could not update launch count: {}", e),
+    }
     Ok(())
 }
 
@@ -46,13 +81,17 @@ pub fn get_installed_apps(refresh: Option<bool>) -> Vec<AppInfo> {
     let mut cached = APP_CACHE.lock();
     if !cached.is_empty() && !refresh.unwrap_or(false) {
         let config = load_config();
+        let rules = RuleSet::from_config(&config);
+        let now = now_secs();
         let updated: Vec<AppInfo> = cached
             .iter()
             .filter(|app| Path::new(&app.path).exists())
             .map(|app| {
                 let mut next = app.clone();
-                next.category = config.categories.get(&next.path).cloned();
+                next.category = assigned(&config, &next.path);
+                next.tags = config.tags.get(&next.path).cloned().unwrap_or_default();
                 next.usage_count = *config.usage_counts.get(&next.path).unwrap_or(&0);
+                rules.apply(&mut next, &config, now);
                 next
             })
             .collect();
@@ -62,6 +101,8 @@ pub fn get_installed_apps(refresh: Option<bool>) -> Vec<AppInfo> {
 
     let mut apps = Vec::new();
     let config = load_config();
+    let rules = RuleSet::from_config(&config);
+    let now = now_secs();
 
     let output = Command::new("mdfind")
         .arg("-onlyin")
@@ -91,7 +132,8 @@ pub fn get_installed_apps(refresh: Option<bool>) -> Vec<AppInfo> {
                 if let Some(name) = path_buf.file_stem().and_then(|s| s.to_str()) {
                     let is_system = path.starts_with("/System/Applications")
                         || path.starts_with("/Applications/Utilities");
-                    let category = config.categories.get(&path).cloned();
+                    let category = assigned(&config, &path);
+                    let tags = config.tags.get(&path).cloned().unwrap_or_default();
                     let usage_count = *config.usage_counts.get(&path).unwrap_or(&0);
                     let icon_data = None;
 
@@ -104,15 +146,23 @@ pub fn get_installed_apps(refresh: Option<bool>) -> Vec<AppInfo> {
                         })
                         .unwrap_or(0);
 
-                    apps.push(AppInfo {
+                    let info = bundle::read_info(&path_buf);
+                    let mut app = AppInfo {
                         name: name.to_string(),
                         path,
                         is_system,
                         category,
+                        tags,
                         usage_count,
                         icon_data,
                         date_modified,
-                    });
+                        architectures: bundle::architectures(&path_buf, &info),
+                        date_installed: bundle::installed_at(&path_buf),
+                        bundle_id: info.bundle_id,
+                        app_category_type: info.category_type,
+                    };
+                    rules.apply(&mut app, &config, now);
+                    apps.push(app);
                 }
             }
         }
@@ -124,8 +174,19 @@ pub fn get_installed_apps(refresh: Option<bool>) -> Vec<AppInfo> {
 
 
 #[tauri::command]
-pub fn reveal_in_finder(path: String) {
-    let _ = Command::new("open").arg("-R").arg(path).spawn();
+pub fn reveal_in_finder(path: String) -> Result<(), AppError> {
+    if !Path::new(&path).exists() {
+        return Err(AppError::not_found(Resource::File, path));
+    }
+    Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| {
+            tracing::warn!("could not reveal in Finder: {}", e);
+            AppError::command("open", e)
+        })
 }
 
 #[cfg(test)]
@@ -149,18 +210,22 @@ mod tests {
                     path: existing_app.to_string_lossy().to_string(),
                     is_system: false,
                     category: None,
+                    tags: vec![],
                     usage_count: 0,
                     icon_data: None,
                     date_modified: 0,
+                    ..Default::default()
                 },
                 AppInfo {
                     name: "Missing".to_string(),
                     path: missing_app.to_string_lossy().to_string(),
                     is_system: false,
                     category: None,
+                    tags: vec![],
                     usage_count: 0,
                     icon_data: None,
                     date_modified: 0,
+                    ..Default::default()
                 },
             ];
         }
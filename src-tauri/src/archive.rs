@@ -0,0 +1,377 @@
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::config::{self, load_config, save_config, AppConfig};
+use crate::error::{AppError, IoAction};
+use crate::icons::get_cache_dir;
+use crate::wallpaper::{local_wallpaper_path, wallpapers_dir};
+
+const CONFIG_ENTRY: &str = "config.json";
+const WALLPAPERS_PREFIX: &str = "wallpapers/";
+const ICONS_PREFIX: &str = "icons/";
+/// Entries larger than this are rejected rather than read into memory.
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    Replace,
+    Merge,
+}
+
+pub(crate) fn add_file(zip: &mut ZipWriter<File>, archive: &Path, name: &str, data: &[u8]) -> Result<(), AppError> {
+    zip.start_file(name, SimpleFileOptions::default())
+        .map_err(|e| AppError::io(IoAction::Write, archive, e))?;
+    zip.write_all(data)
+        .map_err(|e| AppError::io(IoAction::Write, archive, e))
+}
+
+/// Clears the settings that belong to this machine or person rather than
+/// the profile: the sync folder, incognito, the log level and launch counts.
+fn without_local_fields(mut config: AppConfig) -> AppConfig {
+    let defaults = AppConfig::default();
+    config.sync_folder = defaults.sync_folder;
+    config.incognito = defaults.incognito;
+    config.log_level = defaults.log_level;
+    config.usage_counts = defaults.usage_counts;
+    config
+}
+
+/// Keeps `current`'s machine-specific settings in an imported config.
+fn with_local_fields(mut config: AppConfig, current: &AppConfig) -> AppConfig {
+    config.sync_folder = current.sync_folder.clone();
+    config.incognito = current.incognito;
+    config.log_level = current.log_level.clone();
+    config.usage_counts = current.usage_counts.clone();
+    config
+}
+
+pub(crate) fn export_to(path: &Path) -> Result<(), AppError> {
+    let mut config = without_local_fields(load_config());
+    let file = File::create(path).map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    let mut zip = ZipWriter::new(file);
+
+    let wallpaper = config.wallpaper.as_deref().and_then(local_wallpaper_path);
+    if let Some(source) = wallpaper.filter(|path| path.is_file()) {
+        if let Some(name) = source.file_name().map(|n| n.to_string_lossy().to_string()) {
+            let data = fs::read(&source).map_err(|e| AppError::io(IoAction::Read, &source, e))?;
+            add_file(&mut zip, path, &format!("{WALLPAPERS_PREFIX}{name}"), &data)?;
+            config.wallpaper = Some(format!("{WALLPAPERS_PREFIX}{name}"));
+        }
+    }
+
+    if let Ok(read_dir) = fs::read_dir(get_cache_dir()) {
+        for entry in read_dir.flatten() {
+            let icon = entry.path();
+            if !icon.is_file() {
+                continue;
+            }
+            if let Ok(data) = fs::read(&icon) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                add_file(&mut zip, path, &format!("{ICONS_PREFIX}{name}"), &data)?;
+            }
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&config).map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    add_file(&mut zip, path, CONFIG_ENTRY, content.as_bytes())?;
+    zip.finish().map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    Ok(())
+}
+
+/// Writes `data` to `dir/name`. A different file already there is kept and
+/// the entry is written as `name-1`, `name-2`, …; an identical one is reused.
+/// Only the final path component of `name` is used.
+fn extract_into(dir: &Path, name: &str, data: &[u8]) -> Option<PathBuf> {
+    let file_name = Path::new(name).file_name()?;
+    let stem = Path::new(file_name).file_stem().unwrap_or(file_name).to_string_lossy();
+    let extension = Path::new(file_name).extension().map(|e| e.to_string_lossy());
+    let mut dest = dir.join(file_name);
+    for n in 1.. {
+        match fs::read(&dest) {
+            Ok(existing) if existing == data => return Some(dest),
+            Ok(_) => {}
+            Err(_) => break,
+        }
+        dest = dir.join(match &extension {
+            Some(extension) => format!("{stem}-{n}.{extension}"),
+            None => format!("{stem}-{n}"),
+        });
+    }
+    if let Err(e) = fs::write(&dest, data) {
+        tracing::warn!(path = %dest.display(), "could not extract archive entry: {}", e);
+        return None;
+    }
+    Some(dest)
+}
+
+/// Folds `incoming` into `current`. Existing assignments, scripts and
+/// appearance win; new categories and scripts are added and usage counts
+/// take the higher value.
+pub(crate) fn merge_configs(current: &AppConfig, incoming: &AppConfig) -> AppConfig {
+    let mut merged = current.clone();
+    for (path, category) in &incoming.categories {
+        merged
+            .categories
+            .entry(path.clone())
+            .or_insert_with(|| category.clone());
+    }
+    for (path, tags) in &incoming.tags {
+        let merged_tags = merged.tags.entry(path.clone()).or_default();
+        for tag in tags {
+            if !merged_tags.contains(tag) {
+                merged_tags.push(tag.clone());
+            }
+        }
+    }
+    crate::categories::reconcile_tags(&mut merged);
+    for (path, count) in &incoming.usage_counts {
+        let entry = merged.usage_counts.entry(path.clone()).or_insert(0);
+        *entry = (*entry).max(*count);
+    }
+    for category in &incoming.user_categories {
+        if !merged.user_categories.contains(category) {
+            merged.user_categories.push(category.clone());
+        }
+    }
+    merged.category_order = crate::sync::merge_category_order(None, &current.category_order, &incoming.category_order);
+    for script in &incoming.scripts {
+        if !merged.scripts.iter().any(|s| s.name == script.name) {
+            merged.scripts.push(script.clone());
+        }
+    }
+    for smart in &incoming.smart_categories {
+        if !merged.smart_categories.iter().any(|s| s.category == smart.category) {
+            merged.smart_categories.push(smart.clone());
+        }
+    }
+    if merged.wallpaper.is_none() {
+        merged.wallpaper = incoming.wallpaper.clone();
+    }
+    merged
+}
+
+/// Reads an entry, refusing ones larger than `MAX_ENTRY_BYTES`.
+fn read_entry(entry: &mut impl Read, name: &str, archive: &Path) -> Result<Vec<u8>, AppError> {
+    let mut data = vec![];
+    entry
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| AppError::io(IoAction::Read, archive, e))?;
+    if data.len() as u64 > MAX_ENTRY_BYTES {
+        return Err(AppError::InvalidArchive {
+            detail: format!("{} is larger than {} MB", name, MAX_ENTRY_BYTES / 1024 / 1024),
+        });
+    }
+    Ok(data)
+}
+
+pub(crate) fn import_from(path: &Path, mode: ImportMode) -> Result<AppConfig, AppError> {
+    let invalid = |e: zip::result::ZipError| AppError::InvalidArchive { detail: e.to_string() };
+    let file = File::open(path).map_err(|e| AppError::io(IoAction::Read, path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(invalid)?;
+
+    // The config is checked before anything is extracted, so a rejected
+    // archive leaves no files behind.
+    let data = match archive.by_name(CONFIG_ENTRY) {
+        Ok(mut entry) => read_entry(&mut entry, CONFIG_ENTRY, path)?,
+        Err(zip::result::ZipError::FileNotFound) => {
+            return Err(AppError::InvalidArchive {
+                detail: format!("missing {}", CONFIG_ENTRY),
+            })
+        }
+        Err(e) => return Err(invalid(e)),
+    };
+    let parsed = config::parse_config(&String::from_utf8_lossy(&data));
+    if let Some(error) = parsed.error {
+        return Err(AppError::InvalidConfig {
+            source: CONFIG_ENTRY.to_string(),
+            detail: error.to_string(),
+        });
+    }
+    let mut incoming = parsed.config;
+
+    let mut wallpapers: Vec<(String, PathBuf)> = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(invalid)?;
+        if !entry.is_file() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        if let Some(rest) = name.strip_prefix(WALLPAPERS_PREFIX) {
+            let data = read_entry(&mut entry, &name, path)?;
+            if let Some(dest) = extract_into(&wallpapers_dir(), rest, &data) {
+                wallpapers.push((rest.to_string(), dest));
+            }
+        } else if let Some(rest) = name.strip_prefix(ICONS_PREFIX) {
+            // Cached icons are named after the app, so a local one is
+            // never replaced.
+            let local = Path::new(rest).file_name().map(|n| get_cache_dir().join(n));
+            if local.is_some_and(|local| !local.exists()) {
+                extract_into(&get_cache_dir(), rest, &read_entry(&mut entry, &name, path)?);
+            }
+        }
+    }
+
+    if let Some(rest) = incoming
+        .wallpaper
+        .as_deref()
+        .and_then(|w| w.strip_prefix(WALLPAPERS_PREFIX))
+    {
+        incoming.wallpaper = wallpapers
+            .iter()
+            .find(|(name, _)| name == rest)
+            .map(|(_, dest)| dest.to_string_lossy().to_string());
+    }
+
+    let current = load_config();
+    let next = match mode {
+        ImportMode::Replace => with_local_fields(incoming, &current),
+        ImportMode::Merge => merge_configs(&current, &incoming),
+    };
+    let saved = save_config(&next)?;
+    crate::history::clear();
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn export_profile(path: String) -> Result<(), AppError> {
+    export_to(Path::new(&path)).inspect_err(|e| tracing::warn!(path = %path, "profile export failed: {}", e))
+}
+
+#[tauri::command]
+pub fn import_profile(app: AppHandle, path: String, mode: ImportMode) -> Result<AppConfig, AppError> {
+    let config = import_from(Path::new(&path), mode)
+        .inspect_err(|e| tracing::warn!(path = %path, "profile import failed: {}", e))?;
+    crate::apply_config(&app, &config);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{get_config_path, CategoryNode, ScriptAction};
+    use crate::test_support::with_test_home;
+
+    fn script(name: &str) -> ScriptAction {
+        ScriptAction {
+            name: name.to_string(),
+            command: format!("echo {}", name),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn export_then_replace_import_restores_profile() {
+        with_test_home(|home| {
+            let wallpaper = wallpapers_dir().join("wallpaper_1_abc.png");
+            fs::write(&wallpaper, b"image-bytes").unwrap();
+            fs::write(get_cache_dir().join("icon.png"), b"icon-bytes").unwrap();
+            let mut config = load_config();
+            config.wallpaper = Some(wallpaper.to_string_lossy().to_string());
+            config.scripts.push(script("build"));
+            config.user_categories.push("Games".to_string());
+            config.usage_counts.insert("/Xcode.app".into(), 12);
+            config.sync_folder = Some(home.join("Dropbox").to_string_lossy().to_string());
+            config.incognito = true;
+            save_config(&config).unwrap();
+
+            let archive = home.join("profile.zip");
+            export_to(&archive).unwrap();
+
+            // Simulate a fresh machine with its own icon for another app.
+            fs::remove_dir_all(get_config_path()).unwrap();
+            crate::config::reset_cache();
+            fs::write(get_cache_dir().join("other.png"), b"local-icon").unwrap();
+            let mut local = load_config();
+            local.log_level = "debug".into();
+            save_config(&local).unwrap();
+
+            let imported = import_from(&archive, ImportMode::Replace).unwrap();
+            assert_eq!(imported.scripts.len(), 1);
+            assert!(imported.user_categories.contains(&"Games".to_string()));
+            let local = PathBuf::from(imported.wallpaper.unwrap());
+            assert!(local.starts_with(wallpapers_dir()));
+            assert_eq!(fs::read(local).unwrap(), b"image-bytes");
+            assert_eq!(fs::read(get_cache_dir().join("icon.png")).unwrap(), b"icon-bytes");
+            assert_eq!(fs::read(get_cache_dir().join("other.png")).unwrap(), b"local-icon");
+            assert_eq!(load_config().scripts.len(), 1);
+            assert!(imported.usage_counts.is_empty());
+            assert_eq!(imported.sync_folder, None);
+            assert!(!imported.incognito);
+            assert_eq!(imported.log_level, "debug");
+        });
+    }
+
+    #[test]
+    fn merge_keeps_local_settings_and_adds_new_items() {
+        let mut current = AppConfig::default();
+        current.categories.insert("/A.app".into(), "Work".into());
+        current.usage_counts.insert("/A.app".into(), 5);
+        current.scripts.push(script("deploy"));
+        current.shortcut = "Cmd+K".into();
+        current.category_order = vec![CategoryNode::leaf("Development")];
+
+        let mut incoming = AppConfig::default();
+        incoming.categories.insert("/A.app".into(), "Games".into());
+        incoming.categories.insert("/B.app".into(), "Design".into());
+        incoming.usage_counts.insert("/A.app".into(), 9);
+        incoming.scripts.push(script("deploy"));
+        incoming.scripts.push(script("lint"));
+        incoming.user_categories.push("Games".into());
+        incoming.shortcut = "Alt+Space".into();
+        incoming.wallpaper = Some("https://example.com/bg.png".into());
+        incoming.category_order = vec![CategoryNode::leaf("Development"), CategoryNode::leaf("Games")];
+        crate::categories::ensure_node(&mut incoming.category_order, "Development/Databases");
+
+        let merged = merge_configs(&current, &incoming);
+        assert_eq!(merged.categories["/A.app"], "Work");
+        assert_eq!(merged.categories["/B.app"], "Design");
+        assert_eq!(merged.usage_counts["/A.app"], 9);
+        assert_eq!(merged.scripts.len(), 2);
+        assert!(merged.user_categories.contains(&"Games".to_string()));
+        assert_eq!(merged.shortcut, "Cmd+K");
+        assert_eq!(merged.wallpaper.as_deref(), Some("https://example.com/bg.png"));
+        assert_eq!(
+            crate::categories::flatten(&merged.category_order),
+            ["Development", "Development/Databases", "Games"]
+        );
+    }
+
+    #[test]
+    fn keeps_a_different_local_file_with_the_same_name() {
+        with_test_home(|_| {
+            let dir = wallpapers_dir();
+            fs::write(dir.join("bg.png"), b"local").unwrap();
+            assert_eq!(extract_into(&dir, "bg.png", b"local"), Some(dir.join("bg.png")));
+            assert_eq!(extract_into(&dir, "bg.png", b"imported"), Some(dir.join("bg-1.png")));
+            assert_eq!(extract_into(&dir, "../bg.png", b"imported"), Some(dir.join("bg-1.png")));
+            assert_eq!(extract_into(&dir, "bg.png", b"another"), Some(dir.join("bg-2.png")));
+            assert_eq!(fs::read(dir.join("bg.png")).unwrap(), b"local");
+        });
+    }
+
+    #[test]
+    fn rejects_archives_without_config() {
+        with_test_home(|home| {
+            let archive = home.join("empty.zip");
+            let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+            add_file(&mut zip, &archive, "wallpapers/x.png", b"x").unwrap();
+            zip.finish().unwrap();
+            assert!(import_from(&archive, ImportMode::Merge).is_err());
+
+            let archive = home.join("broken.zip");
+            let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+            add_file(&mut zip, &archive, "wallpapers/x.png", b"x").unwrap();
+            add_file(&mut zip, &archive, CONFIG_ENTRY, b"{\"categories\": 3}").unwrap();
+            zip.finish().unwrap();
+            assert_eq!(import_from(&archive, ImportMode::Replace).unwrap_err().code(), "invalid_config");
+            assert!(!wallpapers_dir().join("x.png").exists());
+        });
+    }
+}
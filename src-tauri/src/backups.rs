@@ -0,0 +1,266 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::config::{self, get_config_path, AppConfig};
+use crate::error::{AppError, IoAction, Resource};
+
+const MAX_ROLLING: usize = 20;
+const MAX_DAILY: usize = 14;
+const ROLLING_PREFIX: &str = "config-";
+const DAILY_PREFIX: &str = "daily-";
+/// Fields ignored when deciding whether a save needs a new rolling snapshot.
+const VOLATILE_FIELDS: [&str; 2] = ["revision", "usage_counts"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ConfigBackup {
+    pub id: String,
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub modified_ms: Option<u128>,
+}
+
+fn backups_dir() -> PathBuf {
+    let mut dir = get_config_path();
+    dir.push("backups");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// Backup file names sorted oldest first; the timestamp in the name makes
+/// lexical order chronological.
+fn backup_names(prefix: &str) -> Vec<String> {
+    let Ok(read_dir) = fs::read_dir(backups_dir()) else {
+        return vec![];
+    };
+    let mut names: Vec<String> = read_dir
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|n| n.starts_with(prefix) && n.ends_with(".json"))
+        .collect();
+    names.sort();
+    names
+}
+
+fn prune(prefix: &str, keep: usize) {
+    let names = backup_names(prefix);
+    let excess = names.len().saturating_sub(keep);
+    for name in &names[..excess] {
+        let _ = fs::remove_file(backups_dir().join(name));
+    }
+}
+
+/// The parts of a serialized config worth a snapshot of their own.
+/// Launch counts change on every launch and would push real edits out of
+/// the rolling window.
+fn snapshot_content(content: &str) -> Option<Value> {
+    let mut value: Value = serde_json::from_str(content).ok()?;
+    if let Some(fields) = value.as_object_mut() {
+        for key in VOLATILE_FIELDS {
+            fields.remove(key);
+        }
+    }
+    Some(value)
+}
+
+fn write_rolling(dir: &Path, now: &chrono::DateTime<chrono::Local>, content: &str) {
+    let stamp = now.format("%Y%m%d-%H%M%S%.3f").to_string();
+    let mut n = 0;
+    let mut path = dir.join(format!("{ROLLING_PREFIX}{stamp}-{n:03}.json"));
+    while path.exists() {
+        n += 1;
+        path = dir.join(format!("{ROLLING_PREFIX}{stamp}-{n:03}.json"));
+    }
+    if let Err(e) = fs::write(&path, content) {
+        tracing::warn!(path = %path.display(), "could not write config backup: {}", e);
+    }
+    prune(ROLLING_PREFIX, MAX_ROLLING);
+}
+
+/// Records `content` (the serialized config just written) as a rolling
+/// snapshot, plus the first snapshot of each day. Content that only differs
+/// from the latest snapshot in `VOLATILE_FIELDS` is skipped.
+pub(crate) fn snapshot_config(content: &str) {
+    let dir = backups_dir();
+    let now = chrono::Local::now();
+    let latest = backup_names(ROLLING_PREFIX)
+        .pop()
+        .and_then(|name| fs::read_to_string(dir.join(name)).ok());
+    let unchanged = latest.is_some_and(|latest| {
+        latest == content || snapshot_content(&latest).is_some_and(|l| Some(l) == snapshot_content(content))
+    });
+    if !unchanged {
+        write_rolling(&dir, &now, content);
+    }
+
+    let daily = dir.join(format!("{DAILY_PREFIX}{}.json", now.format("%Y%m%d")));
+    if !daily.exists() {
+        let _ = fs::write(&daily, content);
+        prune(DAILY_PREFIX, MAX_DAILY);
+    }
+}
+
+pub(crate) fn list_backups() -> Vec<ConfigBackup> {
+    let dir = backups_dir();
+    let mut items: Vec<ConfigBackup> = [(ROLLING_PREFIX, "rolling"), (DAILY_PREFIX, "daily")]
+        .iter()
+        .flat_map(|(prefix, kind)| {
+            let dir = dir.clone();
+            backup_names(prefix).into_iter().filter_map(move |id| {
+                let path = dir.join(&id);
+                let meta = fs::metadata(&path).ok()?;
+                Some(ConfigBackup {
+                    path: path.to_string_lossy().to_string(),
+                    kind: kind.to_string(),
+                    size: meta.len(),
+                    modified_ms: meta
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_millis()),
+                    id,
+                })
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| b.modified_ms.cmp(&a.modified_ms).then(b.id.cmp(&a.id)));
+    items
+}
+
+/// Replaces the active config with backup `id`. The current config is
+/// always snapshotted first, so a restore can itself be undone, and the
+/// current launch counts are kept rather than rolled back.
+pub(crate) fn restore_backup(id: &str) -> Result<AppConfig, AppError> {
+    let is_known = backup_names(ROLLING_PREFIX)
+        .into_iter()
+        .chain(backup_names(DAILY_PREFIX))
+        .any(|name| name == id);
+    if !is_known {
+        return Err(AppError::not_found(Resource::Backup, id));
+    }
+    let path = backups_dir().join(id);
+    let content = fs::read_to_string(&path).map_err(|e| AppError::io(IoAction::Read, &path, e))?;
+    let parsed = config::parse_config(&content);
+    if let Some(error) = parsed.error {
+        return Err(AppError::InvalidConfig {
+            source: id.to_string(),
+            detail: error.to_string(),
+        });
+    }
+
+    config::update_config(|current| {
+        match serde_json::to_string_pretty(&*current) {
+            Ok(content) => write_rolling(&backups_dir(), &chrono::Local::now(), &content),
+            Err(e) => tracing::warn!("could not snapshot the config before a restore: {}", e),
+        }
+        let usage_counts = std::mem::take(&mut current.usage_counts);
+        *current = AppConfig {
+            usage_counts,
+            ..parsed.config
+        };
+    })?;
+    crate::history::clear();
+    Ok(config::load_config())
+}
+
+#[tauri::command]
+pub fn list_config_backups() -> Vec<ConfigBackup> {
+    list_backups()
+}
+
+#[tauri::command]
+pub fn restore_config_backup(app: AppHandle, id: String) -> Result<AppConfig, AppError> {
+    let config = restore_backup(&id)?;
+    crate::apply_config(&app, &config);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{load_config, save_config};
+    use crate::test_support::with_test_home;
+
+    fn save_with_shortcut(shortcut: &str) {
+        let mut config = load_config();
+        config.shortcut = shortcut.to_string();
+        save_config(&config).unwrap();
+    }
+
+    #[test]
+    fn keeps_rolling_and_daily_snapshots() {
+        with_test_home(|_| {
+            for i in 0..(MAX_ROLLING + 5) {
+                save_with_shortcut(&format!("Cmd+{}", i));
+            }
+            // Saving identical content does not add a snapshot.
+            save_with_shortcut(&format!("Cmd+{}", MAX_ROLLING + 4));
+
+            let backups = list_backups();
+            let rolling: Vec<_> = backups.iter().filter(|b| b.kind == "rolling").collect();
+            let daily: Vec<_> = backups.iter().filter(|b| b.kind == "daily").collect();
+            assert_eq!(rolling.len(), MAX_ROLLING);
+            assert_eq!(daily.len(), 1);
+
+            let newest = fs::read_to_string(&rolling[0].path).unwrap();
+            assert!(newest.contains(&format!("Cmd+{}", MAX_ROLLING + 4)));
+            let first = fs::read_to_string(&daily[0].path).unwrap();
+            assert!(first.contains("\"Cmd+0\""));
+        });
+    }
+
+    #[test]
+    fn launch_counts_alone_do_not_add_snapshots() {
+        with_test_home(|_| {
+            save_with_shortcut("Cmd+A");
+            let before = list_backups().len();
+            for count in 1..5 {
+                let mut config = load_config();
+                config.usage_counts.insert("/Applications/Slack.app".into(), count);
+                save_config(&config).unwrap();
+            }
+            assert_eq!(list_backups().len(), before);
+            save_with_shortcut("Cmd+B");
+            assert_eq!(list_backups().len(), before + 1);
+        });
+    }
+
+    #[test]
+    fn restores_a_backup_into_the_cache() {
+        with_test_home(|_| {
+            save_with_shortcut("Cmd+A");
+            save_with_shortcut("Cmd+B");
+            let target = list_backups()
+                .into_iter()
+                .find(|b| b.kind == "rolling" && fs::read_to_string(&b.path).unwrap().contains("Cmd+A"))
+                .unwrap();
+
+            // Launch counts alone don't add a snapshot, but the restore does.
+            let mut config = load_config();
+            config.usage_counts.insert("/Applications/Slack.app".into(), 7);
+            save_config(&config).unwrap();
+
+            let restored = restore_backup(&target.id).unwrap();
+            assert_eq!(restored.shortcut, "Cmd+A");
+            assert_eq!(restored.usage_counts["/Applications/Slack.app"], 7);
+            assert_eq!(load_config().shortcut, "Cmd+A");
+            // The config that was replaced is still available, counts included.
+            assert!(list_backups().iter().any(|b| {
+                let content = fs::read_to_string(&b.path).unwrap();
+                content.contains("Cmd+B") && content.contains("\"/Applications/Slack.app\": 7")
+            }));
+        });
+    }
+
+    #[test]
+    fn rejects_unknown_or_outside_backups() {
+        with_test_home(|_| {
+            save_with_shortcut("Cmd+A");
+            assert!(restore_backup("../config.json").is_err());
+            assert!(restore_backup("config-missing.json").is_err());
+        });
+    }
+}
@@ -0,0 +1,128 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// The parts of an app bundle's `Info.plist` we care about.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct BundleInfo {
+    pub bundle_id: Option<String>,
+    /// `LSApplicationCategoryType`, e.g. "public.app-category.developer-tools".
+    pub category_type: Option<String>,
+    pub executable: Option<String>,
+    pub icon_file: Option<String>,
+}
+
+pub(crate) fn read_info(app_path: &Path) -> BundleInfo {
+    let Ok(plist::Value::Dictionary(info)) = plist::Value::from_file(app_path.join("Contents/Info.plist")) else {
+        return BundleInfo::default();
+    };
+    let string = |key: &str| info.get(key).and_then(|v| v.as_string()).map(str::to_string);
+    BundleInfo {
+        bundle_id: string("CFBundleIdentifier"),
+        category_type: string("LSApplicationCategoryType"),
+        executable: string("CFBundleExecutable"),
+        icon_file: string("CFBundleIconFile"),
+    }
+}
+
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+fn arch_name(cpu_type: u32) -> Option<&'static str> {
+    match cpu_type {
+        CPU_TYPE_X86_64 => Some("x86_64"),
+        CPU_TYPE_ARM64 => Some("arm64"),
+        _ => None,
+    }
+}
+
+/// Architectures in a Mach-O header: a thin 64-bit binary or a universal
+/// (fat) one. Only the first bytes of the file are needed.
+pub(crate) fn parse_architectures(header: &[u8]) -> Vec<String> {
+    let word_be = |at: usize| header.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    let word_le = |at: usize| header.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    if word_le(0) == Some(MH_MAGIC_64) {
+        return word_le(4).and_then(arch_name).map(str::to_string).into_iter().collect();
+    }
+    if word_be(0) == Some(FAT_MAGIC) {
+        let count = word_be(4).unwrap_or(0) as usize;
+        // Each fat_arch entry is 20 bytes, starting with its cpu type.
+        return (0..count.min(16))
+            .filter_map(|i| word_be(8 + i * 20))
+            .filter_map(arch_name)
+            .map(str::to_string)
+            .collect();
+    }
+    vec![]
+}
+
+pub(crate) fn architectures(app_path: &Path, info: &BundleInfo) -> Vec<String> {
+    let Some(executable) = &info.executable else {
+        return vec![];
+    };
+    let mut header = vec![0u8; 512];
+    let read = File::open(app_path.join("Contents/MacOS").join(executable)).and_then(|mut f| f.read(&mut header));
+    match read {
+        Ok(len) => parse_architectures(&header[..len]),
+        Err(_) => vec![],
+    }
+}
+
+/// When the app landed on this machine: the bundle's creation time where
+/// the filesystem records one, otherwise its modification time.
+pub(crate) fn installed_at(app_path: &Path) -> u64 {
+    fs::metadata(app_path)
+        .and_then(|m| m.created().or_else(|_| m.modified()))
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_info_plist_fields() {
+        let app = std::env::temp_dir().join(format!("macappcontrol_test_bundle_{}.app", std::process::id()));
+        fs::create_dir_all(app.join("Contents")).unwrap();
+        fs::write(
+            app.join("Contents/Info.plist"),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict>
+  <key>CFBundleIdentifier</key><string>com.jetbrains.intellij</string>
+  <key>CFBundleExecutable</key><string>idea</string>
+  <key>CFBundleIconFile</key><string>idea.icns</string>
+  <key>LSApplicationCategoryType</key><string>public.app-category.developer-tools</string>
+</dict></plist>"#,
+        )
+        .unwrap();
+
+        let info = read_info(&app);
+        assert_eq!(info.bundle_id.as_deref(), Some("com.jetbrains.intellij"));
+        assert_eq!(info.category_type.as_deref(), Some("public.app-category.developer-tools"));
+        assert_eq!(info.executable.as_deref(), Some("idea"));
+        assert_eq!(info.icon_file.as_deref(), Some("idea.icns"));
+        assert_eq!(read_info(&app.join("missing")), BundleInfo::default());
+        let _ = fs::remove_dir_all(&app);
+    }
+
+    #[test]
+    fn parses_thin_and_universal_headers() {
+        let mut thin = MH_MAGIC_64.to_le_bytes().to_vec();
+        thin.extend(CPU_TYPE_ARM64.to_le_bytes());
+        assert_eq!(parse_architectures(&thin), vec!["arm64"]);
+
+        let mut fat = FAT_MAGIC.to_be_bytes().to_vec();
+        fat.extend(2u32.to_be_bytes());
+        for cpu in [CPU_TYPE_X86_64, CPU_TYPE_ARM64] {
+            fat.extend(cpu.to_be_bytes());
+            fat.extend([0u8; 16]);
+        }
+        assert_eq!(parse_architectures(&fat), vec!["x86_64", "arm64"]);
+        assert!(parse_architectures(b"#!/bin/sh").is_empty());
+    }
+}
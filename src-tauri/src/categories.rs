@@ -0,0 +1,620 @@
+use crate::error::{AppError, Resource};
+use crate::config::{update_config_labeled, AppConfig, CategoryNode};
+
+/// Sidebar entries computed from app data rather than stored assignments.
+pub(crate) const VIRTUAL_CATEGORIES: [&str; 4] = ["Frequent", "Scripts", "User Apps", "System"];
+
+const SEPARATOR: char = '/';
+
+/// Trims every segment of a category path and drops empty ones, so
+/// " Development / Databases/" becomes "Development/Databases".
+pub(crate) fn normalize(path: &str) -> Result<String, AppError> {
+    let segments: Vec<&str> = path
+        .split(SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    match segments.first() {
+        None => Err(AppError::InvalidName {
+            resource: Resource::Category,
+            name: path.to_string(),
+        }),
+        Some(root) if VIRTUAL_CATEGORIES.contains(root) => Err(AppError::BuiltInCategory {
+            name: root.to_string(),
+        }),
+        Some(_) => Ok(segments.join("/")),
+    }
+}
+
+/// Whether `category` is `ancestor` or nested anywhere below it.
+pub(crate) fn is_within(category: &str, ancestor: &str) -> bool {
+    category
+        .strip_prefix(ancestor)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(SEPARATOR))
+}
+
+fn parent(path: &str) -> Option<&str> {
+    path.rsplit_once(SEPARATOR).map(|(parent, _)| parent)
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit_once(SEPARATOR).map_or(path, |(_, name)| name)
+}
+
+/// Moves `category` from under `from` to under `to`, if it is within `from`.
+fn reparent(category: &str, from: &str, to: &str) -> Option<String> {
+    is_within(category, from).then(|| format!("{}{}", to, &category[from.len()..]))
+}
+
+/// Every category path in the tree, parents before their children.
+pub(crate) fn flatten(nodes: &[CategoryNode]) -> Vec<String> {
+    fn walk(nodes: &[CategoryNode], prefix: Option<&str>, out: &mut Vec<String>) {
+        for node in nodes {
+            let path = match prefix {
+                Some(prefix) => format!("{}{}{}", prefix, SEPARATOR, node.name),
+                None => node.name.clone(),
+            };
+            out.push(path.clone());
+            walk(&node.children, Some(&path), out);
+        }
+    }
+    let mut out = vec![];
+    walk(nodes, None, &mut out);
+    out
+}
+
+/// Returns the node list that holds `path`'s node (its parent's children),
+/// creating missing ancestors.
+fn siblings_mut<'a>(nodes: &'a mut Vec<CategoryNode>, path: &str) -> &'a mut Vec<CategoryNode> {
+    let Some(parent) = parent(path) else {
+        return nodes;
+    };
+    let parent = ensure_node(nodes, parent);
+    &mut parent.children
+}
+
+pub(crate) fn ensure_node<'a>(nodes: &'a mut Vec<CategoryNode>, path: &str) -> &'a mut CategoryNode {
+    let name = last_segment(path);
+    let siblings = siblings_mut(nodes, path);
+    let index = match siblings.iter().position(|n| n.name == name) {
+        Some(index) => index,
+        None => {
+            siblings.push(CategoryNode::leaf(name));
+            siblings.len() - 1
+        }
+    };
+    &mut siblings[index]
+}
+
+/// Removes the node at `path`, returning it with its former index.
+fn detach(nodes: &mut Vec<CategoryNode>, path: &str) -> Option<(CategoryNode, usize)> {
+    let name = last_segment(path);
+    let siblings = match parent(path) {
+        Some(parent) => &mut find_mut(nodes, parent)?.children,
+        None => nodes,
+    };
+    let index = siblings.iter().position(|n| n.name == name)?;
+    Some((siblings.remove(index), index))
+}
+
+fn find_mut<'a>(nodes: &'a mut [CategoryNode], path: &str) -> Option<&'a mut CategoryNode> {
+    let (first, rest) = match path.split_once(SEPARATOR) {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (path, None),
+    };
+    let node = nodes.iter_mut().find(|n| n.name == first)?;
+    match rest {
+        Some(rest) => find_mut(&mut node.children, rest),
+        None => Some(node),
+    }
+}
+
+/// Folds `source`'s children into `target`, merging same-named subtrees.
+fn merge_nodes(target: &mut CategoryNode, source: CategoryNode) {
+    for child in source.children {
+        match target.children.iter_mut().find(|n| n.name == child.name) {
+            Some(existing) => merge_nodes(existing, child),
+            None => target.children.push(child),
+        }
+    }
+}
+
+fn exists(config: &AppConfig, category: &str) -> bool {
+    config.user_categories.iter().any(|c| is_within(c, category))
+        || config.categories.values().any(|c| is_within(c, category))
+        || config.tags.values().flatten().any(|c| is_within(c, category))
+        || config.smart_categories.iter().any(|smart| is_within(&smart.category, category))
+        || flatten(&config.category_order).iter().any(|c| c == category)
+}
+
+fn validate_source(config: &AppConfig, name: &str) -> Result<String, AppError> {
+    let name = normalize(name)?;
+    if !exists(config, &name) {
+        return Err(AppError::not_found(Resource::Category, name));
+    }
+    Ok(name)
+}
+
+fn dedup(list: &mut Vec<String>) {
+    let mut seen = std::collections::HashSet::new();
+    list.retain(|c| seen.insert(c.clone()));
+}
+
+/// The category an app was assigned by hand. An empty assignment marks an
+/// app the user explicitly left uncategorized, so rules don't pick one.
+pub(crate) fn assigned(config: &AppConfig, app: &str) -> Option<String> {
+    config.categories.get(app).filter(|c| !c.is_empty()).cloned()
+}
+
+/// Brings `tags` in line with `categories`, which older builds (or a synced
+/// config from one) may have changed on their own: each app's category
+/// becomes its first tag, and tags of apps without a category are dropped.
+pub(crate) fn reconcile_tags(config: &mut AppConfig) {
+    let categories = &config.categories;
+    config
+        .tags
+        .retain(|path, _| categories.get(path).is_some_and(|c| !c.is_empty()));
+    for (path, category) in categories.iter().filter(|(_, c)| !c.is_empty()) {
+        let tags = config.tags.entry(path.clone()).or_default();
+        tags.retain(|t| t != category);
+        tags.insert(0, category.clone());
+        dedup(tags);
+    }
+}
+
+/// Rewrites `categories` from the first tag of every app, after `tags`
+/// was edited directly. Apps left uncategorized on purpose stay that way
+/// until they are tagged.
+pub(crate) fn apply_tags(config: &mut AppConfig) {
+    config.tags.retain(|_, tags| {
+        dedup(tags);
+        !tags.is_empty()
+    });
+    let tags = &config.tags;
+    config
+        .categories
+        .retain(|path, category| category.is_empty() && !tags.contains_key(path));
+    config.categories.extend(
+        config
+            .tags
+            .iter()
+            .map(|(path, tags)| (path.clone(), tags[0].clone())),
+    );
+}
+
+/// Runs `f` on every app's tag list and keeps `categories` in step.
+fn edit_tags(config: &mut AppConfig, mut f: impl FnMut(&mut Vec<String>)) {
+    reconcile_tags(config);
+    config.tags.values_mut().for_each(&mut f);
+    apply_tags(config);
+}
+
+/// Makes `category` the app's primary category, keeping its other tags.
+pub(crate) fn set_primary(config: &mut AppConfig, app: &str, category: &str) {
+    reconcile_tags(config);
+    let tags = config.tags.entry(app.to_string()).or_default();
+    if tags.is_empty() {
+        tags.push(category.to_string());
+    } else {
+        tags[0] = category.to_string();
+    }
+    apply_tags(config);
+}
+
+/// Rewrites every stored path under `from` to live under `to`.
+fn move_paths(config: &mut AppConfig, from: &str, to: &str) {
+    for category in config.user_categories.iter_mut() {
+        if let Some(moved) = reparent(category, from, to) {
+            *category = moved;
+        }
+    }
+    dedup(&mut config.user_categories);
+    edit_tags(config, |tags| {
+        for tag in tags.iter_mut() {
+            if let Some(moved) = reparent(tag, from, to) {
+                *tag = moved;
+            }
+        }
+    });
+    for category in config.category_type_map.values_mut() {
+        if let Some(moved) = reparent(category, from, to) {
+            *category = moved;
+        }
+    }
+    for smart in config.smart_categories.iter_mut() {
+        if let Some(moved) = reparent(&smart.category, from, to) {
+            smart.category = moved;
+        }
+    }
+}
+
+/// Adds a category path and any missing parents. Returns the normalized path.
+pub(crate) fn add(config: &mut AppConfig, path: &str) -> Result<String, AppError> {
+    let path = normalize(path)?;
+    let mut ancestor = String::new();
+    for segment in path.split(SEPARATOR) {
+        if !ancestor.is_empty() {
+            ancestor.push(SEPARATOR);
+        }
+        ancestor.push_str(segment);
+        if !config.user_categories.contains(&ancestor) {
+            config.user_categories.push(ancestor.clone());
+        }
+    }
+    ensure_node(&mut config.category_order, &path);
+    Ok(path)
+}
+
+/// Removes `path` and its subtree. Their apps move up to `path`'s parent,
+/// or become uncategorized when `path` is top-level.
+pub(crate) fn remove(config: &mut AppConfig, path: &str) {
+    let Ok(path) = normalize(path) else {
+        return;
+    };
+    let parent = parent(&path).map(str::to_string);
+    config.user_categories.retain(|c| !is_within(c, &path));
+    config.category_type_map.retain(|_, c| !is_within(c, &path));
+    config.smart_categories.retain(|smart| !is_within(&smart.category, &path));
+    edit_tags(config, |tags| {
+        for tag in tags.iter_mut() {
+            if is_within(tag, &path) {
+                *tag = parent.clone().unwrap_or_default();
+            }
+        }
+        tags.retain(|t| !t.is_empty());
+    });
+    detach(&mut config.category_order, &path);
+}
+
+/// Tags an app with an additional category. An untagged app gets it as its
+/// primary category.
+pub(crate) fn add_tag(config: &mut AppConfig, app: &str, tag: &str) -> Result<(), AppError> {
+    let tag = add(config, tag)?;
+    reconcile_tags(config);
+    let tags = config.tags.entry(app.to_string()).or_default();
+    if !tags.contains(&tag) {
+        tags.push(tag);
+    }
+    apply_tags(config);
+    Ok(())
+}
+
+/// Removes a tag from an app. Removing its primary category promotes the
+/// next tag.
+pub(crate) fn remove_tag(config: &mut AppConfig, app: &str, tag: &str) {
+    let Ok(tag) = normalize(tag) else {
+        return;
+    };
+    reconcile_tags(config);
+    if let Some(tags) = config.tags.get_mut(app) {
+        tags.retain(|t| t != &tag);
+    }
+    apply_tags(config);
+}
+
+/// Renames or moves `from` (with its subtree) to the path `to`. Fails if
+/// `to` already exists; use `merge` to combine two categories.
+pub(crate) fn rename(config: &mut AppConfig, from: &str, to: &str) -> Result<(), AppError> {
+    let from = validate_source(config, from)?;
+    let to = normalize(to)?;
+    if from == to {
+        return Ok(());
+    }
+    if is_within(&to, &from) {
+        return Err(AppError::CategoryCycle { from, to });
+    }
+    if exists(config, &to) {
+        return Err(AppError::AlreadyExists {
+            resource: Resource::Category,
+            name: to,
+        });
+    }
+
+    let same_parent = parent(&from) == parent(&to);
+    let detached = detach(&mut config.category_order, &from);
+    if let Some(to_parent) = parent(&to) {
+        add(config, to_parent)?;
+    }
+    move_paths(config, &from, &to);
+    let (mut node, index) = detached.unwrap_or_else(|| (CategoryNode::leaf(""), usize::MAX));
+    node.name = last_segment(&to).to_string();
+    let siblings = siblings_mut(&mut config.category_order, &to);
+    let index = if same_parent { index.min(siblings.len()) } else { siblings.len() };
+    siblings.insert(index, node);
+    Ok(())
+}
+
+/// Moves every app and subcategory in `from` into `into` and removes
+/// `from`. Subcategories with the same name are merged too.
+pub(crate) fn merge(config: &mut AppConfig, from: &str, into: &str) -> Result<(), AppError> {
+    let from = validate_source(config, from)?;
+    let into = normalize(into)?;
+    if from == into {
+        return Err(AppError::CategoryCycle { from, to: into });
+    }
+    if is_within(&into, &from) {
+        return Err(AppError::CategoryCycle { from, to: into });
+    }
+    if !exists(config, &into) {
+        return rename(config, &from, &into);
+    }
+
+    let detached = detach(&mut config.category_order, &from);
+    add(config, &into)?;
+    move_paths(config, &from, &into);
+    if let Some((node, _)) = detached {
+        merge_nodes(ensure_node(&mut config.category_order, &into), node);
+    }
+    Ok(())
+}
+
+pub(crate) fn app_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_stem()
+        .map_or_else(|| path.to_string(), |s| s.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn add_app_tag(path: String, tag: String) -> Result<(), AppError> {
+    let label = format!("Tagged {} with {}", app_name(&path), tag.trim());
+    update_config_labeled(label, |config| add_tag(config, &path, &tag))?
+}
+
+#[tauri::command]
+pub fn remove_app_tag(path: String, tag: String) -> Result<(), AppError> {
+    let label = format!("Removed tag {} from {}", tag.trim(), app_name(&path));
+    update_config_labeled(label, |config| remove_tag(config, &path, &tag))
+}
+
+#[tauri::command]
+pub fn rename_category(from: String, to: String) -> Result<(), AppError> {
+    let label = format!("Renamed category {} to {}", from, to.trim());
+    update_config_labeled(label, |config| rename(config, &from, &to))?
+}
+
+#[tauri::command]
+pub fn merge_categories(from: String, into: String) -> Result<(), AppError> {
+    let label = format!("Merged category {} into {}", from, into.trim());
+    update_config_labeled(label, |config| merge(config, &from, &into))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smart_categories::{Rule, SmartCategory};
+
+    fn config() -> AppConfig {
+        let mut config = AppConfig::default();
+        add(&mut config, "Dev").unwrap();
+        config.categories.insert("/Xcode.app".into(), "Dev".into());
+        config.categories.insert("/iTerm.app".into(), "Dev".into());
+        config.categories.insert("/VSCode.app".into(), "Development".into());
+        config.categories.insert("/Figma.app".into(), "Design".into());
+        config
+    }
+
+    fn smart(category: &str) -> SmartCategory {
+        SmartCategory {
+            category: category.to_string(),
+            rules: vec![Rule::BundleIdPrefix {
+                prefix: "com.jetbrains.".to_string(),
+            }],
+            match_all: false,
+        }
+    }
+
+    fn smart_categories(config: &AppConfig) -> Vec<&str> {
+        config.smart_categories.iter().map(|s| s.category.as_str()).collect()
+    }
+
+    fn order(config: &AppConfig) -> Vec<String> {
+        flatten(&config.category_order)
+    }
+
+    fn position(config: &AppConfig, path: &str) -> Option<usize> {
+        order(config).iter().position(|c| c == path)
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize(" Development / Databases/").unwrap(), "Development/Databases");
+        assert!(normalize(" / ").is_err());
+        assert!(normalize("System/Tools").is_err());
+        assert!(is_within("Development/Databases", "Development"));
+        assert!(is_within("Development", "Development"));
+        assert!(!is_within("DevelopmentTools", "Development"));
+    }
+
+    #[test]
+    fn add_creates_parents_in_tree() {
+        let mut config = config();
+        add(&mut config, "Development/Databases/SQL").unwrap();
+        add(&mut config, "Research/Papers").unwrap();
+        let order = order(&config);
+        let dev = order.iter().position(|c| c == "Development").unwrap();
+        assert_eq!(order[dev + 1], "Development/Databases");
+        assert_eq!(order[dev + 2], "Development/Databases/SQL");
+        assert!(config.user_categories.contains(&"Research".to_string()));
+        assert!(config.user_categories.contains(&"Research/Papers".to_string()));
+    }
+
+    #[test]
+    fn rename_keeps_position_and_assignments() {
+        let mut config = config();
+        let before = position(&config, "Design");
+        rename(&mut config, "Design", "  UI Design ").unwrap();
+        assert_eq!(position(&config, "UI Design"), before);
+        assert!(config.user_categories.contains(&"UI Design".to_string()));
+        assert!(!config.user_categories.contains(&"Design".to_string()));
+        assert_eq!(config.categories["/Figma.app"], "UI Design");
+        assert_eq!(config.categories["/Xcode.app"], "Dev");
+        assert_eq!(config.category_type_map["graphics-design"], "UI Design");
+    }
+
+    #[test]
+    fn rename_rejects_collisions_and_builtins() {
+        let mut config = config();
+        config.smart_categories.push(smart("JetBrains"));
+        let before = config.clone();
+        assert!(rename(&mut config, "Dev", "Development").is_err());
+        assert!(rename(&mut config, "Dev", "JetBrains").is_err());
+        assert!(rename(&mut config, "Dev", "System").is_err());
+        assert!(rename(&mut config, "Frequent", "Popular").is_err());
+        assert!(rename(&mut config, "Missing", "Other").is_err());
+        assert!(rename(&mut config, "Dev", " ").is_err());
+        assert!(rename(&mut config, "Dev", "Dev/Inner").is_err());
+        assert_eq!(config.user_categories, before.user_categories);
+        assert_eq!(config.categories, before.categories);
+        assert_eq!(config.category_order, before.category_order);
+    }
+
+    #[test]
+    fn moving_a_subtree_moves_its_apps() {
+        let mut config = config();
+        add(&mut config, "Development/Databases/SQL").unwrap();
+        config.categories.insert("/TablePlus.app".into(), "Development/Databases".into());
+        config.categories.insert("/DBeaver.app".into(), "Development/Databases/SQL".into());
+        config.smart_categories.push(smart("Development/Databases/SQL"));
+        config.smart_categories.push(smart("Development"));
+
+        rename(&mut config, "Development/Databases", "Data/Stores").unwrap();
+        assert_eq!(smart_categories(&config), ["Data/Stores/SQL", "Development"]);
+        assert_eq!(config.categories["/TablePlus.app"], "Data/Stores");
+        assert_eq!(config.categories["/DBeaver.app"], "Data/Stores/SQL");
+        assert_eq!(config.categories["/VSCode.app"], "Development");
+        let order = order(&config);
+        assert!(order.contains(&"Data/Stores/SQL".to_string()));
+        assert!(!order.iter().any(|c| c.starts_with("Development/")));
+        assert!(config.user_categories.contains(&"Data".to_string()));
+        assert!(!config.user_categories.iter().any(|c| c.starts_with("Development/")));
+    }
+
+    #[test]
+    fn removing_a_subtree_moves_apps_to_its_parent() {
+        let mut config = config();
+        add(&mut config, "Development/Databases/SQL").unwrap();
+        config.categories.insert("/DBeaver.app".into(), "Development/Databases/SQL".into());
+        config.categories.insert("/TablePlus.app".into(), "Development/Databases".into());
+        config.smart_categories.push(smart("Development/Databases/SQL"));
+        config.smart_categories.push(smart("Development"));
+
+        remove(&mut config, "Development/Databases");
+        assert_eq!(smart_categories(&config), ["Development"]);
+        assert_eq!(config.categories["/DBeaver.app"], "Development");
+        assert_eq!(config.categories["/TablePlus.app"], "Development");
+        assert!(!order(&config).iter().any(|c| c.starts_with("Development/")));
+
+        // Top-level categories leave their apps uncategorized.
+        remove(&mut config, "Development");
+        assert!(!config.categories.contains_key("/DBeaver.app"));
+        assert!(!config.categories.contains_key("/VSCode.app"));
+        assert_eq!(position(&config, "Development"), None);
+        assert!(!config.category_type_map.contains_key("developer-tools"));
+        assert!(config.smart_categories.is_empty());
+    }
+
+    #[test]
+    fn merge_moves_apps_and_removes_source() {
+        let mut config = config();
+        merge(&mut config, "Dev", "Development").unwrap();
+        assert!(!config.user_categories.contains(&"Dev".to_string()));
+        assert_eq!(position(&config, "Dev"), None);
+        assert_eq!(order(&config).iter().filter(|c| *c == "Development").count(), 1);
+        assert!(config.categories.values().all(|c| c == "Development" || c == "Design"));
+        assert_eq!(config.categories["/Xcode.app"], "Development");
+
+        assert!(merge(&mut config, "Design", "Design").is_err());
+        assert!(merge(&mut config, "Dev", "Design").is_err());
+    }
+
+    #[test]
+    fn merge_combines_nested_subtrees() {
+        let mut config = config();
+        add(&mut config, "Dev/Tools").unwrap();
+        add(&mut config, "Development/Tools").unwrap();
+        add(&mut config, "Dev/Databases").unwrap();
+        config.categories.insert("/Proxyman.app".into(), "Dev/Tools".into());
+
+        merge(&mut config, "Dev", "Development").unwrap();
+        assert_eq!(config.categories["/Proxyman.app"], "Development/Tools");
+        let order = order(&config);
+        assert_eq!(order.iter().filter(|c| *c == "Development/Tools").count(), 1);
+        assert!(order.contains(&"Development/Databases".to_string()));
+        assert!(!order.iter().any(|c| is_within(c, "Dev")));
+        assert!(merge(&mut config, "Development", "Development/Tools").is_err());
+    }
+
+    #[test]
+    fn merge_into_new_category_takes_source_position() {
+        let mut config = config();
+        let before = position(&config, "Dev");
+        merge(&mut config, "Dev", "Engineering").unwrap();
+        assert_eq!(position(&config, "Engineering"), before);
+        assert_eq!(config.categories["/iTerm.app"], "Engineering");
+    }
+
+    #[test]
+    fn explicit_uncategorization_survives_tag_edits() {
+        let mut config = config();
+        config.categories.insert("/Xcode.app".into(), String::new());
+        add_tag(&mut config, "/Figma.app", "Work").unwrap();
+        remove_tag(&mut config, "/Figma.app", "Work");
+        assert_eq!(config.categories["/Xcode.app"], "");
+        assert!(!config.tags.contains_key("/Xcode.app"));
+        assert_eq!(assigned(&config, "/Xcode.app"), None);
+
+        add_tag(&mut config, "/Xcode.app", "Work").unwrap();
+        assert_eq!(assigned(&config, "/Xcode.app").as_deref(), Some("Work"));
+    }
+
+    #[test]
+    fn tags_keep_categories_in_step() {
+        let mut config = config();
+        add_tag(&mut config, "/Figma.app", "Work").unwrap();
+        add_tag(&mut config, "/Figma.app", "Work").unwrap();
+        assert_eq!(config.tags["/Figma.app"], vec!["Design", "Work"]);
+        assert_eq!(config.categories["/Figma.app"], "Design");
+        assert!(config.user_categories.contains(&"Work".to_string()));
+
+        // An untagged app gets its first tag as its category.
+        add_tag(&mut config, "/Notion.app", "Work/Docs").unwrap();
+        assert_eq!(config.categories["/Notion.app"], "Work/Docs");
+
+        remove_tag(&mut config, "/Figma.app", "Design");
+        assert_eq!(config.categories["/Figma.app"], "Work");
+        remove_tag(&mut config, "/Figma.app", "Work");
+        assert!(!config.categories.contains_key("/Figma.app"));
+        assert!(!config.tags.contains_key("/Figma.app"));
+    }
+
+    #[test]
+    fn category_changes_update_every_tag() {
+        let mut config = config();
+        add(&mut config, "Work/Clients").unwrap();
+        add_tag(&mut config, "/Xcode.app", "Work/Clients").unwrap();
+        add_tag(&mut config, "/Figma.app", "Work").unwrap();
+
+        rename(&mut config, "Work", "Job").unwrap();
+        assert_eq!(config.tags["/Xcode.app"], vec!["Dev", "Job/Clients"]);
+        merge(&mut config, "Dev", "Development").unwrap();
+        assert_eq!(config.tags["/Xcode.app"], vec!["Development", "Job/Clients"]);
+
+        remove(&mut config, "Job/Clients");
+        assert_eq!(config.tags["/Xcode.app"], vec!["Development", "Job"]);
+        remove(&mut config, "Design");
+        assert_eq!(config.categories["/Figma.app"], "Job");
+    }
+
+    #[test]
+    fn reconciles_categories_edited_without_tags() {
+        let mut config = config();
+        add_tag(&mut config, "/Figma.app", "Work").unwrap();
+        // As an older build would: change the category, drop another app.
+        config.categories.insert("/Figma.app".into(), "Social".into());
+        config.categories.remove("/Xcode.app");
+        reconcile_tags(&mut config);
+        assert_eq!(config.tags["/Figma.app"], vec!["Social", "Design", "Work"]);
+        assert!(!config.tags.contains_key("/Xcode.app"));
+        assert_eq!(config.tags["/iTerm.app"], vec!["Dev"]);
+    }
+}
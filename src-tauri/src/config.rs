@@ -1,25 +1,65 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::error::{AppError, IoAction};
+use crate::migrations::{migrate, CURRENT_SCHEMA_VERSION};
+use crate::smart_categories::SmartCategory;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ScriptAction {
     pub name: String,
     pub command: String,
     pub cwd: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+/// A sidebar entry. Its full path is the names from the root joined with
+/// `/`, e.g. "Development/Databases"; `children` are in display order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CategoryNode {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<CategoryNode>,
+}
+
+impl CategoryNode {
+    pub fn leaf(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            children: vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppConfig {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Bumped on every write; whole-config saves must carry the current value.
+    #[serde(default)]
+    pub revision: u64,
+    /// Primary category per app. Kept alongside `tags` for older builds.
     pub categories: HashMap<String, String>,
+    /// Every category path an app is tagged with; the first is its primary
+    /// category and always equals its `categories` entry.
+    #[serde(default)]
+    pub tags: HashMap<String, Vec<String>>,
     pub usage_counts: HashMap<String, u32>,
     pub user_categories: Vec<String>,
     pub shortcut: String,
     #[serde(default)]
     pub scripts: Vec<ScriptAction>,
     #[serde(default)]
-    pub category_order: Vec<String>,
+    pub category_order: Vec<CategoryNode>,
+    /// Rule-based categories, applied to apps without a manual assignment.
+    #[serde(default)]
+    pub smart_categories: Vec<SmartCategory>,
+    /// Category for apps that declare an `LSApplicationCategoryType`
+    /// (without the "public.app-category." prefix) and match nothing else.
+    #[serde(default = "default_category_type_map")]
+    pub category_type_map: HashMap<String, String>,
     #[serde(default = "default_theme")]
     pub theme: String,
     pub wallpaper: Option<String>,
@@ -31,12 +71,40 @@ pub struct AppConfig {
     pub wallpaper_fit: String,
     #[serde(default = "default_wallpaper_position")]
     pub wallpaper_position: String,
+    #[serde(default = "default_file_search_roots")]
+    pub file_search_roots: Vec<String>,
+    #[serde(default = "default_file_search_ignore")]
+    pub file_search_ignore: Vec<String>,
+    /// Shared folder (Dropbox, iCloud Drive, a git checkout) to sync with.
+    /// Machine-specific, so it is never synced itself.
+    #[serde(default)]
+    pub sync_folder: Option<String>,
+    /// Launches are not counted while on. Machine-specific like `sync_folder`.
+    #[serde(default)]
+    pub incognito: bool,
+    /// Launch data older than this many days is pruned; `None` keeps it all.
+    #[serde(default)]
+    pub usage_retention_days: Option<u32>,
+    /// "en", "zh-CN" or "system" to follow the OS language.
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Least severe level written to the log files. Machine-specific.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
 }
 
 fn default_theme() -> String {
     "Midnight".to_string()
 }
 
+fn default_locale() -> String {
+    "system".to_string()
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
 fn default_wallpaper_blur() -> f32 {
     10.0
 }
@@ -53,20 +121,47 @@ fn default_wallpaper_position() -> String {
     "center".to_string()
 }
 
+fn default_file_search_roots() -> Vec<String> {
+    vec!["~/Documents".to_string(), "~/Projects".to_string()]
+}
+
+fn default_category_type_map() -> HashMap<String, String> {
+    [
+        ("developer-tools", "Development"),
+        ("social-networking", "Social"),
+        ("graphics-design", "Design"),
+        ("photography", "Design"),
+        ("productivity", "Productivity"),
+        ("business", "Productivity"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+fn default_file_search_ignore() -> Vec<String> {
+    vec!["node_modules".to_string(), "target".to_string()]
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
-        let core_categories = vec![
-            "Frequent".to_string(),
-            "Scripts".to_string(),
-            "Development".to_string(),
-            "Social".to_string(),
-            "Design".to_string(),
-            "Productivity".to_string(),
-            "User Apps".to_string(),
-            "System".to_string(),
-        ];
+        let core_categories = [
+            "Frequent",
+            "Scripts",
+            "Development",
+            "Social",
+            "Design",
+            "Productivity",
+            "User Apps",
+            "System",
+        ]
+        .map(CategoryNode::leaf)
+        .to_vec();
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            revision: 0,
             categories: HashMap::new(),
+            tags: HashMap::new(),
             usage_counts: HashMap::new(),
             user_categories: vec![
                 "Development".to_string(),
@@ -77,12 +172,21 @@ impl Default for AppConfig {
             shortcut: "Alt+Space".to_string(),
             scripts: vec![],
             category_order: core_categories,
+            smart_categories: vec![],
+            category_type_map: default_category_type_map(),
             theme: default_theme(),
             wallpaper: None,
             wallpaper_blur: default_wallpaper_blur(),
             wallpaper_overlay: default_wallpaper_overlay(),
             wallpaper_fit: default_wallpaper_fit(),
             wallpaper_position: default_wallpaper_position(),
+            file_search_roots: default_file_search_roots(),
+            file_search_ignore: default_file_search_ignore(),
+            sync_folder: None,
+            incognito: false,
+            usage_retention_days: None,
+            locale: default_locale(),
+            log_level: default_log_level(),
         }
     }
 }
@@ -94,66 +198,318 @@ pub(crate) fn get_config_path() -> PathBuf {
     path
 }
 
+/// Expands a leading `~/` to the user's home directory.
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
 use once_cell::sync::Lazy;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 static CONFIG_CACHE: Lazy<RwLock<Option<AppConfig>>> = Lazy::new(|| RwLock::new(None));
+static LOAD_ERROR: Lazy<Mutex<Option<ConfigLoadError>>> = Lazy::new(|| Mutex::new(None));
+/// The same error, kept after the frontend has taken it so diagnostics can
+/// still report it.
+static LAST_LOAD_ERROR: Lazy<Mutex<Option<ConfigLoadError>>> = Lazy::new(|| Mutex::new(None));
+/// `config.json` as this process last read or wrote it, used to tell our own
+/// writes apart from external edits.
+static DISK_CONTENT: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+/// Held across every read-modify-write of the config so concurrent commands
+/// cannot lose each other's changes.
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Why `config.json` could not be read as-is. Sent to the frontend so the
+/// user knows their file was moved aside rather than silently reset.
+#[derive(Serialize, Clone, Debug)]
+pub struct ConfigLoadError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub backup_path: Option<String>,
+}
+
+pub(crate) struct ParsedConfig {
+    pub config: AppConfig,
+    pub error: Option<serde_json::Error>,
+}
+
+#[cfg(test)]
+pub(crate) fn reset_cache() {
+    *CONFIG_CACHE.write() = None;
+    *LOAD_ERROR.lock() = None;
+    *LAST_LOAD_ERROR.lock() = None;
+    *DISK_CONTENT.lock() = None;
+}
+
+/// Drops commas directly before `}` or `]`, the most common hand-edit typo.
+fn strip_trailing_commas(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in content.chars() {
+        if in_string {
+            in_string = c != '"' || escaped;
+            escaped = c == '\\' && !escaped;
+        } else if c == '"' {
+            in_string = true;
+        } else if c == '}' || c == ']' {
+            let trimmed = out.trim_end().len();
+            if out[..trimmed].ends_with(',') {
+                out.truncate(trimmed - 1);
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn accepts_field(defaults: &Value, key: &str, candidate: &Value) -> bool {
+    let mut trial = defaults.clone();
+    trial[key] = candidate.clone();
+    serde_json::from_value::<AppConfig>(trial).is_ok()
+}
+
+/// Builds a config from whichever fields of `value` are valid, falling back
+/// to defaults for the rest. Maps and lists are salvaged entry by entry so
+/// one bad usage count or script doesn't discard its siblings. Each field and
+/// entry is checked on its own against the defaults, so the work stays linear
+/// in the size of the file.
+fn recover_fields(value: &Value) -> AppConfig {
+    let Some(fields) = value.as_object() else {
+        return AppConfig::default();
+    };
+    let defaults = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+    let mut merged = defaults.clone();
+    for (key, field) in fields {
+        if accepts_field(&defaults, key, field) {
+            merged[key] = field.clone();
+            continue;
+        }
+        let salvaged = match field {
+            Value::Object(entries) => Value::Object(
+                entries
+                    .iter()
+                    .filter(|(k, v)| {
+                        let single = Map::from_iter([((*k).clone(), (*v).clone())]);
+                        accepts_field(&defaults, key, &Value::Object(single))
+                    })
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .filter(|item| accepts_field(&defaults, key, &Value::Array(vec![(*item).clone()])))
+                    .cloned()
+                    .collect(),
+            ),
+            _ => continue,
+        };
+        if accepts_field(&defaults, key, &salvaged) {
+            merged[key] = salvaged;
+        }
+    }
+    serde_json::from_value(merged).unwrap_or_default()
+}
+
+/// Parses `config.json` content. On failure the error is returned alongside
+/// the best config that could be recovered from the file.
+pub(crate) fn parse_config(content: &str) -> ParsedConfig {
+    let strict = serde_json::from_str::<Value>(content)
+        .map(migrate)
+        .and_then(serde_json::from_value::<AppConfig>);
+    let error = match strict {
+        Ok(config) => return ParsedConfig { config, error: None },
+        // Re-parse the raw text so the error carries a line and column.
+        Err(e) => serde_json::from_str::<AppConfig>(content).err().unwrap_or(e),
+    };
+
+    let config = serde_json::from_str::<Value>(content)
+        .or_else(|_| serde_json::from_str::<Value>(&strip_trailing_commas(content)))
+        .map(|value| recover_fields(&migrate(value)))
+        .unwrap_or_default();
+    ParsedConfig {
+        config,
+        error: Some(error),
+    }
+}
+
+/// Renames an unreadable JSON file to `<name>.corrupt-<timestamp>.json` next
+/// to it and returns the new path.
+pub(crate) fn move_aside(path: &Path) -> Option<String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let name = path.file_stem().map_or_else(|| "config".into(), |stem| stem.to_string_lossy());
+    let backup = path.with_file_name(format!("{}.corrupt-{}.json", name, stamp));
+    fs::rename(path, &backup)
+        .or_else(|_| fs::copy(path, &backup).map(|_| ()))
+        .ok()?;
+    Some(backup.to_string_lossy().to_string())
+}
+
+pub(crate) fn config_file_path() -> PathBuf {
+    get_config_path().join("config.json")
+}
 
 pub fn load_config() -> AppConfig {
     if let Some(config) = CONFIG_CACHE.read().as_ref() {
         return config.clone();
     }
 
-    let mut path = get_config_path();
-    path.push("config.json");
-    let mut config: AppConfig = if let Ok(content) = fs::read_to_string(path) {
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        AppConfig::default()
+    let path = config_file_path();
+    let config = match fs::read_to_string(&path) {
+        Ok(content) => {
+            let parsed = parse_config(&content);
+            *DISK_CONTENT.lock() = parsed.error.is_none().then_some(content);
+            if let Some(error) = parsed.error {
+                let error = ConfigLoadError {
+                    message: error.to_string(),
+                    line: error.line(),
+                    column: error.column(),
+                    backup_path: move_aside(&path),
+                };
+                *LAST_LOAD_ERROR.lock() = Some(error.clone());
+                *LOAD_ERROR.lock() = Some(error);
+            }
+            parsed.config
+        }
+        Err(_) => AppConfig::default(),
     };
+    let config = with_category_order(config);
+
+    *CONFIG_CACHE.write() = Some(config.clone());
+    config
+}
 
-    config.category_order.retain(|c| c != "All");
+/// An empty sidebar order would hide every category, so it is refilled with
+/// the built-in entries followed by the user's categories.
+fn with_category_order(mut config: AppConfig) -> AppConfig {
     if config.category_order.is_empty() {
-        let mut default_order = vec![
-            "Frequent".to_string(),
-            "Scripts".to_string(),
-            "Development".to_string(),
-            "Social".to_string(),
-            "Design".to_string(),
-            "Productivity".to_string(),
-            "User Apps".to_string(),
-            "System".to_string(),
-        ];
-        for cat in &config.user_categories {
-            if !default_order.contains(cat) {
-                default_order.push(cat.clone());
-            }
+        config.category_order = AppConfig::default().category_order;
+        for category in config.user_categories.clone() {
+            crate::categories::ensure_node(&mut config.category_order, &category);
         }
-        config.category_order = default_order;
     }
-    
-    *CONFIG_CACHE.write() = Some(config.clone());
     config
 }
 
-pub fn save_config(config: &AppConfig) {
+/// Re-reads `config.json` after it changed on disk. Returns `Ok(None)` when
+/// the content is what we last read or wrote ourselves, and leaves the cache
+/// untouched when the new content does not parse.
+pub(crate) fn reload_from_disk() -> Result<Option<AppConfig>, ConfigLoadError> {
+    let _guard = WRITE_LOCK.lock();
+    let Ok(content) = fs::read_to_string(config_file_path()) else {
+        return Ok(None);
+    };
+    if DISK_CONTENT.lock().as_deref() == Some(content.as_str()) {
+        return Ok(None);
+    }
+    let parsed = parse_config(&content);
+    if let Some(error) = parsed.error {
+        return Err(ConfigLoadError {
+            message: error.to_string(),
+            line: error.line(),
+            column: error.column(),
+            backup_path: None,
+        });
+    }
+    let config = with_category_order(parsed.config);
+    *DISK_CONTENT.lock() = Some(content);
     *CONFIG_CACHE.write() = Some(config.clone());
-    
-    let mut path = get_config_path();
-    path.push("config.json");
-    
+    crate::history::clear();
+    Ok(Some(config))
+}
+
+fn same_content(a: &AppConfig, b: &AppConfig) -> bool {
+    let mut a = serde_json::to_value(a).unwrap_or_default();
+    let mut b = serde_json::to_value(b).unwrap_or_default();
+    a["revision"] = Value::Null;
+    b["revision"] = Value::Null;
+    a == b
+}
+
+/// Writes `config` as the next revision. Callers must hold `WRITE_LOCK`.
+/// Saving what is already on disk is a no-op.
+fn store(mut config: AppConfig) -> Result<AppConfig, AppError> {
+    let current = load_config();
+    if DISK_CONTENT.lock().is_some() && same_content(&config, &current) {
+        return Ok(current);
+    }
+    config.revision = current.revision + 1;
+
+    let path = config_file_path();
+    let content = serde_json::to_string_pretty(&config).map_err(|e| AppError::io(IoAction::Write, &path, e))?;
     // Safety: write to .tmp then rename
     let tmp_path = path.with_extension("json.tmp");
-    if let Ok(content) = serde_json::to_string_pretty(config) {
-        if fs::write(&tmp_path, content).is_ok() {
-            let _ = fs::rename(tmp_path, path);
-        }
+    fs::write(&tmp_path, &content)
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .map_err(|e| {
+            tracing::error!(path = %path.display(), "could not save config: {}", e);
+            AppError::io(IoAction::Write, &path, e)
+        })?;
+    *DISK_CONTENT.lock() = Some(content.clone());
+    *CONFIG_CACHE.write() = Some(config.clone());
+    crate::backups::snapshot_config(&content);
+    Ok(config)
+}
+
+/// Replaces the config unconditionally and returns it with its new revision.
+/// Prefer `update_config` for changes based on the current config.
+pub fn save_config(config: &AppConfig) -> Result<AppConfig, AppError> {
+    let _guard = WRITE_LOCK.lock();
+    store(config.clone())
+}
+
+/// Runs `f` on the current config and saves the result if it changed,
+/// holding the write lock for the whole cycle. Fails only if the config
+/// could not be written; `f`'s own result is passed through.
+pub fn update_config<R>(f: impl FnOnce(&mut AppConfig) -> R) -> Result<R, AppError> {
+    let _guard = WRITE_LOCK.lock();
+    let current = load_config();
+    let mut config = current.clone();
+    let result = f(&mut config);
+    if !same_content(&config, &current) {
+        store(config)?;
+    }
+    Ok(result)
+}
+
+/// Like `update_config`, but records the change under `label` so it can be
+/// undone.
+pub fn update_config_labeled<R>(
+    label: impl Into<String>,
+    f: impl FnOnce(&mut AppConfig) -> R,
+) -> Result<R, AppError> {
+    let _guard = WRITE_LOCK.lock();
+    let current = load_config();
+    let mut config = current.clone();
+    let result = f(&mut config);
+    if !same_content(&config, &current) {
+        let saved = store(config)?;
+        crate::history::record(label.into(), current, saved);
+    }
+    Ok(result)
+}
+
+/// Saves a whole config sent by the frontend, unless the config changed
+/// since the frontend read it.
+pub(crate) fn save_if_current(config: AppConfig) -> Result<AppConfig, AppError> {
+    let _guard = WRITE_LOCK.lock();
+    let current = load_config().revision;
+    if config.revision != current {
+        return Err(AppError::StaleConfig {
+            revision: config.revision,
+            current,
+        });
     }
+    store(config)
 }
 
 #[tauri::command]
-pub fn save_config_command(config: AppConfig) {
-    save_config(&config);
+pub fn save_config_command(config: AppConfig) -> Result<AppConfig, AppError> {
+    save_if_current(config)
 }
 
 #[tauri::command]
@@ -161,26 +517,235 @@ pub fn get_config() -> AppConfig {
     load_config()
 }
 
+/// Returns (once) the error from the last load of a corrupt `config.json`.
 #[tauri::command]
-pub fn add_category(category: String) {
-    let mut config = load_config();
-    if !config.user_categories.contains(&category) {
-        config.user_categories.push(category);
-        save_config(&config);
-    }
+pub fn take_config_load_error() -> Option<ConfigLoadError> {
+    LOAD_ERROR.lock().take()
+}
+
+pub(crate) fn pending_load_error() -> Option<ConfigLoadError> {
+    LOAD_ERROR.lock().clone()
+}
+
+/// The error from loading `config.json` this session, whether or not the
+/// frontend has taken it yet.
+pub(crate) fn last_load_error() -> Option<ConfigLoadError> {
+    LAST_LOAD_ERROR.lock().clone()
+}
+
+#[tauri::command]
+pub fn add_category(category: String) -> Result<(), AppError> {
+    update_config_labeled(format!("Added category {}", category), |config| {
+        crate::categories::add(config, &category).map(|_| ())
+    })?
 }
 
+/// Removes a category and everything nested under it. Their apps move to
+/// the removed category's parent, or become uncategorized at the top level.
 #[tauri::command]
-pub fn remove_category(category: String) {
-    let mut config = load_config();
-    config.user_categories.retain(|c| c != &category);
-    config.categories.retain(|_, v| v != &category);
-    save_config(&config);
+pub fn remove_category(category: String) -> Result<(), AppError> {
+    update_config_labeled(format!("Removed category {}", category), |config| {
+        crate::categories::remove(config, &category)
+    })
 }
 
+/// Assigns an app to a category path such as "Development/Databases",
+/// creating missing categories. An empty path leaves the app uncategorized,
+/// without falling back to smart categories or its declared type.
 #[tauri::command]
-pub fn update_app_category(path: String, category: String) {
-    let mut config = load_config();
-    config.categories.insert(path, category);
-    save_config(&config);
+pub fn update_app_category(path: String, category: String) -> Result<(), AppError> {
+    let app_name = Path::new(&path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    update_config_labeled(format!("Moved {} to {}", app_name, category), |config| {
+        if category.trim().is_empty() {
+            config.categories.insert(path.clone(), String::new());
+            config.tags.remove(&path);
+            return Ok(());
+        }
+        let category = crate::categories::add(config, &category)?;
+        crate::categories::set_primary(config, &path, &category);
+        Ok(())
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_test_home;
+
+    #[test]
+    fn valid_config_parses_without_error() {
+        let parsed = parse_config(&serde_json::to_string(&AppConfig::default()).unwrap());
+        assert!(parsed.error.is_none());
+    }
+
+    #[test]
+    fn refills_an_emptied_category_order_on_load() {
+        with_test_home(|_| {
+            let mut config = AppConfig::default();
+            config.user_categories.push("Games".into());
+            config.category_order.clear();
+            fs::write(config_file_path(), serde_json::to_string(&config).unwrap()).unwrap();
+
+            let order = crate::categories::flatten(&load_config().category_order);
+            assert_eq!(order.first().map(String::as_str), Some("Frequent"));
+            assert_eq!(order.last().map(String::as_str), Some("Games"));
+        });
+    }
+
+    #[test]
+    fn recovers_valid_fields_around_bad_ones() {
+        let parsed = parse_config(
+            r#"{
+                "categories": {"/Applications/Figma.app": "Design", "/Applications/Bad.app": 7},
+                "usage_counts": {"/Applications/Figma.app": 3, "/Applications/Slack.app": "x"},
+                "user_categories": ["Design"],
+                "shortcut": "Cmd+K",
+                "scripts": [{"name": "ok", "command": "ls"}, {"name": "broken"}],
+                "wallpaper_blur": "heavy",
+                "theme": "Forest"
+            }"#,
+        );
+        let error = parsed.error.expect("type errors are reported");
+        assert_eq!(error.line(), 2);
+        let config = parsed.config;
+        assert_eq!(config.categories.len(), 1);
+        assert_eq!(config.usage_counts.get("/Applications/Figma.app"), Some(&3));
+        assert_eq!(config.usage_counts.len(), 1);
+        assert_eq!(config.shortcut, "Cmd+K");
+        assert_eq!(config.scripts.len(), 1);
+        assert_eq!(config.theme, "Forest");
+        assert_eq!(config.wallpaper_blur, 10.0);
+    }
+
+    #[test]
+    fn salvages_large_maps_entry_by_entry() {
+        let mut counts: Map<String, Value> = (0..5_000).map(|i| (format!("/App{}.app", i), Value::from(i))).collect();
+        counts.insert("/Bad.app".into(), Value::from("x"));
+        let content = serde_json::json!({"categories": {}, "usage_counts": counts}).to_string();
+        let parsed = parse_config(&content);
+        assert!(parsed.error.is_some());
+        assert_eq!(parsed.config.usage_counts.len(), 5_000);
+        assert_eq!(parsed.config.usage_counts["/App7.app"], 7);
+    }
+
+    #[test]
+    fn recovers_from_trailing_commas() {
+        let parsed = parse_config(
+            r#"{
+                "categories": {},
+                "usage_counts": {},
+                "user_categories": ["Games", "Work",],
+                "shortcut": "Alt+Space",
+                "theme": "a,]",
+            }"#,
+        );
+        let error = parsed.error.expect("syntax errors are reported");
+        assert_eq!(error.line(), 4);
+        assert!(error.column() > 0);
+        assert_eq!(parsed.config.user_categories, vec!["Games", "Work"]);
+        assert_eq!(parsed.config.theme, "a,]");
+    }
+
+    #[test]
+    fn unreadable_json_falls_back_to_defaults() {
+        let parsed = parse_config("{ this is not json");
+        assert!(parsed.error.is_some());
+        assert_eq!(parsed.config.shortcut, AppConfig::default().shortcut);
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside_and_reported() {
+        with_test_home(|_| {
+            let path = get_config_path().join("config.json");
+            fs::write(&path, r#"{"shortcut": "Cmd+J", "usage_counts": []}"#).unwrap();
+
+            let config = load_config();
+            assert_eq!(config.shortcut, "Cmd+J");
+            assert!(!path.exists());
+
+            let error = take_config_load_error().expect("error is surfaced");
+            let backup = PathBuf::from(error.backup_path.unwrap());
+            assert!(backup.is_file());
+            assert!(backup
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("config.corrupt-"));
+            assert!(take_config_load_error().is_none());
+            assert!(last_load_error().is_some());
+        });
+    }
+
+    #[test]
+    fn reloads_external_edits_only() {
+        with_test_home(|_| {
+            let mut config = load_config();
+            config.shortcut = "Cmd+J".to_string();
+            save_config(&config).unwrap();
+            assert!(reload_from_disk().unwrap().is_none());
+
+            let edited = fs::read_to_string(config_file_path())
+                .unwrap()
+                .replace("Cmd+J", "Ctrl+Shift+P");
+            fs::write(config_file_path(), edited).unwrap();
+            let reloaded = reload_from_disk().unwrap().expect("external edit is picked up");
+            assert_eq!(reloaded.shortcut, "Ctrl+Shift+P");
+            assert_eq!(load_config().shortcut, "Ctrl+Shift+P");
+            assert!(reload_from_disk().unwrap().is_none());
+
+            fs::write(config_file_path(), "{ \"shortcut\": ").unwrap();
+            let error = reload_from_disk().unwrap_err();
+            assert_eq!(error.line, 1);
+            assert!(error.backup_path.is_none());
+            assert_eq!(load_config().shortcut, "Ctrl+Shift+P");
+        });
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        with_test_home(|_| {
+            let threads: Vec<_> = (0..8)
+                .map(|_| {
+                    std::thread::spawn(|| {
+                        for _ in 0..25 {
+                            update_config(|config| {
+                                *config.usage_counts.entry("/A.app".into()).or_insert(0) += 1;
+                            })
+                            .unwrap();
+                        }
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+            let config = load_config();
+            assert_eq!(config.usage_counts["/A.app"], 200);
+            assert_eq!(config.revision, 200);
+        });
+    }
+
+    #[test]
+    fn stale_whole_config_saves_are_rejected() {
+        with_test_home(|_| {
+            let mut frontend = load_config();
+            add_category("Games".to_string()).unwrap();
+
+            frontend.theme = "Forest".to_string();
+            assert!(save_if_current(frontend).is_err());
+            assert_eq!(load_config().theme, default_theme());
+
+            let mut fresh = load_config();
+            fresh.theme = "Forest".to_string();
+            let saved = save_if_current(fresh).unwrap();
+            assert_eq!(saved.revision, 2);
+            assert!(saved.user_categories.contains(&"Games".to_string()));
+
+            // Saving unchanged content keeps the revision.
+            assert_eq!(save_if_current(saved).unwrap().revision, 2);
+        });
+    }
 }
@@ -0,0 +1,367 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::categories::{self, VIRTUAL_CATEGORIES};
+use crate::config::{load_config, update_config_labeled, AppConfig};
+use crate::error::{render, AppError};
+use crate::i18n::{self, Locale, LOCALE_SETTINGS};
+use crate::logging::LOG_LEVELS;
+
+pub(crate) const THEMES: [&str; 4] = ["Midnight", "Dawn", "Nebula", "Forest"];
+pub(crate) const WALLPAPER_FITS: [&str; 2] = ["cover", "contain"];
+pub(crate) const WALLPAPER_POSITIONS: [&str; 5] = ["center", "top", "bottom", "left", "right"];
+const MAX_WALLPAPER_BLUR: f32 = 20.0;
+
+/// Fields managed by the backend that a patch may not touch.
+const READ_ONLY_FIELDS: [&str; 2] = ["schema_version", "revision"];
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    /// Stable identifier of the problem, e.g. "one_of"; `message` is its
+    /// text in the user's language.
+    pub code: String,
+    pub message: String,
+}
+
+/// What is wrong with a patched field, and the values its message needs.
+type Problem = (&'static str, Vec<(&'static str, String)>);
+
+impl FieldError {
+    fn new(locale: Locale, field: &str, (code, context): Problem) -> Self {
+        let context: Map<String, Value> = context
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), Value::String(value)))
+            .collect();
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: render(i18n::tr(locale, &format!("field.{}", code)), &context),
+        }
+    }
+}
+
+/// Applies an RFC 7396 JSON Merge Patch: objects merge recursively, `null`
+/// removes a key and anything else replaces the target.
+pub(crate) fn merge_patch(target: &mut Value, patch: &Value) {
+    let Some(patch_fields) = patch.as_object() else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let target_fields = target.as_object_mut().expect("target is an object");
+    for (key, value) in patch_fields {
+        if value.is_null() {
+            target_fields.remove(key);
+        } else {
+            merge_patch(target_fields.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// The first name listed twice in `names`.
+fn duplicate<'a>(names: impl IntoIterator<Item = &'a String>) -> Option<Problem> {
+    let mut seen = std::collections::HashSet::new();
+    names
+        .into_iter()
+        .find(|name| !seen.insert(name.as_str()))
+        .map(|name| ("duplicate", vec![("name", name.clone())]))
+}
+
+fn validate_values(config: &AppConfig, field: &str) -> Option<Problem> {
+    let between = |min: f32, max: f32| ("between", vec![("min", min.to_string()), ("max", max.to_string())]);
+    let one_of = |values: &[&str]| ("one_of", vec![("values", values.join(", "))]);
+    match field {
+        "wallpaper_blur" if !(0.0..=MAX_WALLPAPER_BLUR).contains(&config.wallpaper_blur) => {
+            Some(between(0.0, MAX_WALLPAPER_BLUR))
+        }
+        "wallpaper_overlay" if !(0.0..=1.0).contains(&config.wallpaper_overlay) => Some(between(0.0, 1.0)),
+        "wallpaper_fit" if !WALLPAPER_FITS.contains(&config.wallpaper_fit.as_str()) => Some(one_of(&WALLPAPER_FITS)),
+        "wallpaper_position" if !WALLPAPER_POSITIONS.contains(&config.wallpaper_position.as_str()) => {
+            Some(one_of(&WALLPAPER_POSITIONS))
+        }
+        "theme" if !THEMES.contains(&config.theme.as_str()) => {
+            Some(("unknown_theme", vec![("name", config.theme.clone())]))
+        }
+        "locale" if !LOCALE_SETTINGS.contains(&config.locale.as_str()) => Some(one_of(&LOCALE_SETTINGS)),
+        "log_level" if !LOG_LEVELS.contains(&config.log_level.as_str()) => Some(one_of(&LOG_LEVELS)),
+        "shortcut" if config.shortcut.trim().is_empty() => Some(("empty", vec![])),
+        "usage_retention_days" if config.usage_retention_days == Some(0) => {
+            Some(("at_least", vec![("min", "1".to_string())]))
+        }
+        "category_type_map" => config
+            .category_type_map
+            .values()
+            .find(|c| !config.user_categories.contains(c))
+            .map(|c| ("unknown_category", vec![("name", c.clone())])),
+        "user_categories" => config
+            .user_categories
+            .iter()
+            .find(|c| categories::normalize(c).ok().as_ref() != Some(*c))
+            .map(|c| ("invalid_category", vec![("name", c.clone())]))
+            .or_else(|| duplicate(&config.user_categories)),
+        "category_order" => {
+            let paths = categories::flatten(&config.category_order);
+            let known = |path: &String| {
+                VIRTUAL_CATEGORIES.contains(&path.as_str())
+                    || config.user_categories.iter().any(|c| categories::is_within(c, path))
+            };
+            paths
+                .iter()
+                .find(|path| !known(path))
+                .map(|path| ("unknown_category", vec![("name", path.clone())]))
+                .or_else(|| duplicate(&paths))
+        }
+        _ => None,
+    }
+}
+
+/// Returns `current` with `patch` applied, or an error for every patched
+/// field that is unknown, read-only, mistyped or out of range.
+pub(crate) fn patched(current: &AppConfig, patch: &Value) -> Result<AppConfig, Vec<FieldError>> {
+    let locale = i18n::for_config(current);
+    let invalid = |e: serde_json::Error| ("invalid_value", vec![("detail", e.to_string())]);
+    let Some(fields) = patch.as_object() else {
+        return Err(vec![FieldError::new(locale, "", ("not_an_object", vec![]))]);
+    };
+    let current_v = serde_json::to_value(current).unwrap_or_default();
+    let mut errors = vec![];
+    let mut well_typed = Map::new();
+    for (field, value) in fields {
+        if READ_ONLY_FIELDS.contains(&field.as_str()) {
+            errors.push(FieldError::new(locale, field, ("read_only", vec![])));
+            continue;
+        }
+        if current_v.get(field).is_none() {
+            errors.push(FieldError::new(locale, field, ("unknown_field", vec![])));
+            continue;
+        }
+        // Check each field on its own so one bad value doesn't hide another.
+        let mut candidate = current_v.clone();
+        merge_patch(&mut candidate, &Value::Object(Map::from_iter([(field.clone(), value.clone())])));
+        match serde_json::from_value::<AppConfig>(candidate) {
+            Ok(_) => {
+                well_typed.insert(field.clone(), value.clone());
+            }
+            Err(e) => errors.push(FieldError::new(locale, field, invalid(e))),
+        }
+    }
+    // Values are checked together, since one field may refer to another
+    // (a new category and its place in the sidebar).
+    let mut candidate = current_v.clone();
+    let well_typed = Value::Object(well_typed);
+    merge_patch(&mut candidate, &well_typed);
+    if let Ok(config) = serde_json::from_value::<AppConfig>(candidate) {
+        for field in well_typed.as_object().into_iter().flat_map(|fields| fields.keys()) {
+            if let Some(problem) = validate_values(&config, field) {
+                errors.push(FieldError::new(locale, field, problem));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut next = current_v;
+    merge_patch(&mut next, patch);
+    let mut next: AppConfig =
+        serde_json::from_value(next).map_err(|e| vec![FieldError::new(locale, "", invalid(e))])?;
+    // Keep `categories` and `tags` in step, whichever of them was patched.
+    if fields.contains_key("categories") {
+        categories::reconcile_tags(&mut next);
+    } else if fields.contains_key("tags") {
+        categories::apply_tags(&mut next);
+    }
+    Ok(next)
+}
+
+/// Undo label for a patch: the field name for single-field patches.
+fn label(patch: &Value) -> String {
+    match patch.as_object().map(|fields| fields.keys().collect::<Vec<_>>()).as_deref() {
+        Some([field]) => format!("Changed {}", field.replace('_', " ")),
+        _ => "Changed settings".to_string(),
+    }
+}
+
+/// Applies `patch` to the current config in one transaction.
+pub(crate) fn patch(patch: &Value) -> Result<AppConfig, AppError> {
+    let folder_changed = update_config_labeled(label(patch), |config| {
+        let next = patched(config, patch).map_err(|errors| AppError::InvalidFields { errors })?;
+        let folder_changed = next.sync_folder != config.sync_folder;
+        *config = next;
+        Ok::<_, AppError>(folder_changed)
+    })??;
+    if folder_changed {
+        crate::sync::reset_base();
+    }
+    Ok(load_config())
+}
+
+#[tauri::command]
+pub fn patch_config(app: AppHandle, patch: Value) -> Result<AppConfig, AppError> {
+    let config = self::patch(&patch)?;
+    let changed = |field: &str| patch.get(field).is_some();
+    if changed("shortcut") {
+        crate::shortcuts::register_app_shortcut(&app, &config.shortcut);
+    }
+    if changed("scripts") || changed("incognito") || changed("locale") {
+        crate::update_tray_menu(&app);
+    }
+    if changed("log_level") {
+        crate::logging::set_level(&config.log_level);
+    }
+    if changed("usage_retention_days") {
+        crate::usage::enforce_retention()?;
+    }
+    if changed("file_search_roots") || changed("file_search_ignore") {
+        crate::files::start_file_index();
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::update_config;
+    use crate::test_support::with_test_home;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn merge_patch_follows_rfc_7396() {
+        let mut target = json!({"a": "b", "c": {"d": "e", "f": "g"}});
+        merge_patch(&mut target, &json!({"a": "z", "c": {"f": null}}));
+        assert_eq!(target, json!({"a": "z", "c": {"d": "e"}}));
+
+        let mut target = json!({"a": [1, 2]});
+        merge_patch(&mut target, &json!({"a": [3], "b": {"c": null}}));
+        assert_eq!(target, json!({"a": [3], "b": {}}));
+    }
+
+    #[test]
+    fn applies_valid_patches_and_keeps_other_fields() {
+        with_test_home(|_| {
+            crate::config::add_category("Games".to_string()).unwrap();
+            let config = patch(&json!({
+                "theme": "Forest",
+                "wallpaper_blur": 4.0,
+                "categories": {"/Figma.app": "Design"},
+                "category_type_map": {"games": "Games", "business": null}
+            }))
+            .unwrap();
+            assert_eq!(config.theme, "Forest");
+            assert_eq!(config.wallpaper_blur, 4.0);
+            assert_eq!(config.categories["/Figma.app"], "Design");
+            assert!(config.user_categories.contains(&"Games".to_string()));
+            assert_eq!(config.category_type_map["games"], "Games");
+            assert!(!config.category_type_map.contains_key("business"));
+            assert_eq!(config.category_type_map["developer-tools"], "Development");
+
+            let config = patch(&json!({"categories": {"/Figma.app": null}, "wallpaper": null})).unwrap();
+            assert!(config.categories.is_empty());
+            assert_eq!(load_config().revision, config.revision);
+        });
+    }
+
+    #[test]
+    fn keeps_tags_in_step_and_resets_the_sync_base() {
+        with_test_home(|_| {
+            let config = patch(&json!({"categories": {"/Figma.app": "Design"}})).unwrap();
+            assert_eq!(config.tags["/Figma.app"], ["Design"]);
+            let config = patch(&json!({"tags": {"/Figma.app": ["Development", "Design"]}})).unwrap();
+            assert_eq!(config.categories["/Figma.app"], "Development");
+
+            let base = crate::config::get_config_path().join("sync-base.json");
+            fs::write(&base, "{}").unwrap();
+            patch(&json!({"theme": "Dawn"})).unwrap();
+            assert!(base.exists());
+            patch(&json!({"sync_folder": "~/Dropbox"})).unwrap();
+            assert!(!base.exists());
+        });
+    }
+
+    #[test]
+    fn rejects_bad_category_lists() {
+        with_test_home(|_| {
+            let code = |patch_value: Value| match patch(&patch_value) {
+                Err(AppError::InvalidFields { errors }) => errors[0].code.clone(),
+                other => panic!("expected invalid fields, got {:?}", other),
+            };
+            assert_eq!(code(json!({"user_categories": ["Games", "Games"]})), "duplicate");
+            assert_eq!(code(json!({"user_categories": [" "]})), "invalid_category");
+            assert_eq!(code(json!({"user_categories": ["System"]})), "invalid_category");
+            assert_eq!(code(json!({"category_order": [{"name": "Nope"}]})), "unknown_category");
+            assert_eq!(code(json!({"category_order": [{"name": "Design"}, {"name": "Design"}]})), "duplicate");
+
+            let config = patch(&json!({
+                "user_categories": ["Development/Databases"],
+                "category_order": [{"name": "Frequent"}, {"name": "Development", "children": [{"name": "Databases"}]}]
+            }))
+            .unwrap();
+            assert_eq!(
+                categories::flatten(&config.category_order),
+                ["Frequent", "Development", "Development/Databases"]
+            );
+        });
+    }
+
+    #[test]
+    fn reports_every_invalid_field_and_changes_nothing() {
+        with_test_home(|_| {
+            update_config(|c| c.locale = "en".into()).unwrap();
+            let before = load_config();
+            let error = patch(&json!({
+                "theme": "Neon",
+                "wallpaper_blur": 42,
+                "wallpaper_fit": "stretch",
+                "wallpaper_position": "middle",
+                "shortcut": 3,
+                "revision": 99,
+                "colour": "red",
+                "user_categories": ["Work"],
+                "category_type_map": {"games": "Nope"}
+            }))
+            .unwrap_err();
+            assert_eq!(error.code(), "invalid_fields");
+            let AppError::InvalidFields { mut errors } = error else {
+                unreachable!()
+            };
+            errors.sort_by(|a, b| a.field.cmp(&b.field));
+            let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+            assert_eq!(
+                fields,
+                vec![
+                    "category_type_map",
+                    "colour",
+                    "revision",
+                    "shortcut",
+                    "theme",
+                    "wallpaper_blur",
+                    "wallpaper_fit",
+                    "wallpaper_position"
+                ]
+            );
+            let fit = errors.iter().find(|e| e.field == "wallpaper_fit").unwrap();
+            assert_eq!(fit.code, "one_of");
+            assert_eq!(fit.message, "must be one of cover, contain");
+            let after = load_config();
+            assert_eq!(after.revision, before.revision);
+            assert_eq!(after.user_categories, before.user_categories);
+        });
+    }
+
+    #[test]
+    fn field_messages_follow_the_locale() {
+        with_test_home(|_| {
+            update_config(|c| c.locale = "zh-CN".into()).unwrap();
+            let Err(AppError::InvalidFields { errors }) = patch(&json!({"theme": "Neon"})) else {
+                panic!("theme should be rejected")
+            };
+            assert_eq!(errors[0].code, "unknown_theme");
+            assert_eq!(errors[0].message, "未知的主题“Neon”");
+        });
+    }
+}
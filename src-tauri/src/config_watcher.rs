@@ -0,0 +1,53 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::config;
+
+static CONFIG_WATCHER: Lazy<Mutex<Option<RecommendedWatcher>>> = Lazy::new(|| Mutex::new(None));
+
+/// Watches the config dir (not the file itself, since editors often replace
+/// the file on save) and applies external edits to `config.json`.
+pub(crate) fn watch_config(app: &AppHandle) {
+    let app = app.clone();
+    let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        let touches_config = event
+            .paths
+            .iter()
+            .any(|p| p.file_name().is_some_and(|n| n == "config.json"));
+        if !touches_config {
+            return;
+        }
+        match config::reload_from_disk() {
+            Ok(Some(config)) => {
+                tracing::info!(revision = config.revision, "applied external config edit");
+                crate::apply_config(&app, &config)
+            }
+            Ok(None) => {}
+            Err(error) => {
+                tracing::warn!(
+                    line = error.line,
+                    column = error.column,
+                    "external config edit is invalid: {}",
+                    error.message
+                );
+                let _ = app.emit("config-load-error", error);
+            }
+        }
+    });
+
+    let mut guard = CONFIG_WATCHER.lock();
+    *guard = None;
+    if let Ok(mut watcher) = watcher {
+        if watcher
+            .watch(&config::get_config_path(), RecursiveMode::NonRecursive)
+            .is_ok()
+        {
+            *guard = Some(watcher);
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use zip::ZipWriter;
+
+use crate::archive::add_file;
+use crate::config::{
+    config_file_path, get_config_path, last_load_error, load_config, parse_config, AppConfig, ConfigLoadError,
+};
+use crate::error::{AppError, IoAction};
+use crate::i18n::{self, Locale};
+use crate::icons::get_cache_dir;
+use crate::logging::logs_dir;
+use crate::shortcuts::parse_shortcut;
+use crate::wallpaper::local_wallpaper_path;
+
+/// Above this the icon cache is reported as a warning.
+const ICON_CACHE_WARN_BYTES: u64 = 200 * 1024 * 1024;
+const REPORT_ENTRY: &str = "report.json";
+const CONFIG_ENTRY: &str = "config.json";
+const LOGS_PREFIX: &str = "logs/";
+const REDACTED: &str = "<redacted>";
+/// Only the newest part of each log file goes into a support bundle.
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Check {
+    /// Stable id, e.g. "spotlight".
+    pub id: &'static str,
+    pub title: String,
+    pub status: CheckStatus,
+    /// What was found, in plain English for support.
+    pub detail: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiagnosticsReport {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub checks: Vec<Check>,
+    pub icon_cache_files: usize,
+    pub icon_cache_bytes: u64,
+}
+
+impl Check {
+    fn new(id: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            title: String::new(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    fn localized(mut self, locale: Locale) -> Self {
+        self.title = i18n::tr(locale, &format!("diagnostics.{}", self.id)).to_string();
+        self
+    }
+}
+
+fn check_spotlight() -> Check {
+    let output = Command::new("mdfind")
+        .arg("-count")
+        .arg("-onlyin")
+        .arg("/Applications")
+        .arg("kMDItemContentTypeTree == 'com.apple.application-bundle'")
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            let count: usize = String::from_utf8_lossy(&output.stdout).trim().parse().unwrap_or(0);
+            if count > 0 {
+                Check::new("spotlight", CheckStatus::Ok, format!("{} apps indexed in /Applications", count))
+            } else {
+                Check::new(
+                    "spotlight",
+                    CheckStatus::Warning,
+                    "mdfind found no apps; Spotlight indexing may be off",
+                )
+            }
+        }
+        Ok(output) => Check::new(
+            "spotlight",
+            CheckStatus::Error,
+            format!("mdfind exited with {}", output.status),
+        ),
+        Err(e) => Check::new("spotlight", CheckStatus::Error, format!("mdfind could not run: {}", e)),
+    }
+}
+
+fn check_swift() -> Check {
+    match Command::new("swift").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            Check::new("swift", CheckStatus::Ok, version.lines().next().unwrap_or_default().trim())
+        }
+        Ok(output) => Check::new(
+            "swift",
+            CheckStatus::Warning,
+            format!("swift exited with {}; icons stored only in asset catalogs cannot be extracted", output.status),
+        ),
+        Err(e) => Check::new(
+            "swift",
+            CheckStatus::Warning,
+            format!("swift is not available ({}); icons stored only in asset catalogs cannot be extracted", e),
+        ),
+    }
+}
+
+fn check_config_dir(dir: &Path) -> Check {
+    let probe = dir.join(".write-test");
+    match fs::write(&probe, b"ok") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            Check::new("config_dir", CheckStatus::Ok, dir.display().to_string())
+        }
+        Err(e) => Check::new(
+            "config_dir",
+            CheckStatus::Error,
+            format!("{} is not writable: {}", dir.display(), e),
+        ),
+    }
+}
+
+/// `load_error` is the error from loading `config.json` at startup, if it
+/// was corrupt. The file has been moved aside by then, so what is on disk
+/// now parses.
+fn check_config_file(path: &Path, load_error: Option<ConfigLoadError>) -> Check {
+    if let Some(error) = load_error {
+        let moved = error
+            .backup_path
+            .map_or_else(String::new, |backup| format!("; the original was moved to {}", backup));
+        return Check::new(
+            "config_file",
+            CheckStatus::Error,
+            format!("line {}, column {}: {}{}", error.line, error.column, error.message, moved),
+        );
+    }
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Check::new("config_file", CheckStatus::Ok, "no config file yet; using defaults");
+        }
+        Err(e) => return Check::new("config_file", CheckStatus::Error, format!("could not read: {}", e)),
+    };
+    match parse_config(&content).error {
+        None => Check::new("config_file", CheckStatus::Ok, format!("{} parses", path.display())),
+        Some(error) => Check::new(
+            "config_file",
+            CheckStatus::Error,
+            format!("line {}, column {}: {}", error.line(), error.column(), error),
+        ),
+    }
+}
+
+fn check_shortcut(app: &AppHandle, shortcut: &str) -> Check {
+    match parse_shortcut(shortcut) {
+        None => Check::new(
+            "shortcut",
+            CheckStatus::Error,
+            format!("\"{}\" is not a supported shortcut", shortcut),
+        ),
+        Some(parsed) if app.global_shortcut().is_registered(parsed) => {
+            Check::new("shortcut", CheckStatus::Ok, format!("{} is registered", shortcut))
+        }
+        Some(_) => Check::new(
+            "shortcut",
+            CheckStatus::Error,
+            format!("{} is not registered; another app may be using it", shortcut),
+        ),
+    }
+}
+
+fn check_wallpaper(wallpaper: Option<&str>) -> Check {
+    let Some(wallpaper) = wallpaper.filter(|w| !w.trim().is_empty()) else {
+        return Check::new("wallpaper", CheckStatus::Ok, "no wallpaper set");
+    };
+    match local_wallpaper_path(wallpaper) {
+        None => Check::new("wallpaper", CheckStatus::Ok, "remote wallpaper, not checked"),
+        Some(path) if path.is_file() => Check::new("wallpaper", CheckStatus::Ok, path.display().to_string()),
+        Some(path) => Check::new(
+            "wallpaper",
+            CheckStatus::Error,
+            format!("{} does not exist", path.display()),
+        ),
+    }
+}
+
+/// Number and total size of the files directly in `dir`.
+fn dir_size(dir: &Path) -> (usize, u64) {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|meta| meta.is_file())
+        .fold((0, 0), |(files, bytes), meta| (files + 1, bytes + meta.len()))
+}
+
+fn check_icon_cache(files: usize, bytes: u64) -> Check {
+    let status = if bytes > ICON_CACHE_WARN_BYTES {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    };
+    let detail = format!("{} icons, {:.1} MB", files, bytes as f64 / (1024.0 * 1024.0));
+    Check::new("icon_cache", status, detail)
+}
+
+fn collect(app: &AppHandle, config: &AppConfig) -> DiagnosticsReport {
+    let (icon_cache_files, icon_cache_bytes) = dir_size(&get_cache_dir());
+    let locale = i18n::current();
+    let checks = [
+        check_spotlight(),
+        check_swift(),
+        check_config_dir(&get_config_path()),
+        check_config_file(&config_file_path(), last_load_error()),
+        check_shortcut(app, &config.shortcut),
+        check_wallpaper(config.wallpaper.as_deref()),
+        check_icon_cache(icon_cache_files, icon_cache_bytes),
+    ];
+    for check in checks.iter().filter(|c| c.status != CheckStatus::Ok) {
+        tracing::warn!(check = check.id, status = ?check.status, "diagnostics: {}", check.detail);
+    }
+    DiagnosticsReport {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        checks: checks.into_iter().map(|check| check.localized(locale)).collect(),
+        icon_cache_files,
+        icon_cache_bytes,
+    }
+}
+
+fn to_json(value: &impl Serialize, path: &Path) -> Result<String, AppError> {
+    serde_json::to_string_pretty(value).map_err(|e| AppError::io(IoAction::Write, path, e))
+}
+
+/// The config as shared in a support bundle, without script commands,
+/// launch history, file search roots or the sync folder path. Plugin
+/// filesystem grants live in the plugin manifests, which are not bundled.
+fn redacted_config(config: &AppConfig) -> Value {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    if let Some(scripts) = value.get_mut("scripts").and_then(Value::as_array_mut) {
+        for script in scripts {
+            script["command"] = Value::from(REDACTED);
+            if script.get("cwd").is_some_and(|cwd| !cwd.is_null()) {
+                script["cwd"] = Value::from(REDACTED);
+            }
+        }
+    }
+    value["usage_counts"] = Value::from(REDACTED);
+    value["file_search_roots"] = config.file_search_roots.iter().map(|_| REDACTED).collect();
+    if config.sync_folder.is_some() {
+        value["sync_folder"] = Value::from(REDACTED);
+    }
+    value
+}
+
+/// The last `max_bytes` of a log file, starting at a whole line.
+fn log_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut data = Vec::new();
+    if len <= max_bytes {
+        file.read_to_end(&mut data)?;
+        return Ok(data);
+    }
+    file.seek(SeekFrom::Start(len - max_bytes))?;
+    file.take(max_bytes).read_to_end(&mut data)?;
+    let start = data.iter().position(|&b| b == b'\n').map_or(data.len(), |i| i + 1);
+    Ok(data.split_off(start))
+}
+
+/// Zips the report with the redacted config and the newest part of each
+/// log file.
+fn write_bundle(path: &Path, report: &DiagnosticsReport, config: &AppConfig) -> Result<(), AppError> {
+    let file = File::create(path).map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    let mut zip = ZipWriter::new(file);
+    add_file(&mut zip, path, REPORT_ENTRY, to_json(report, path)?.as_bytes())?;
+    add_file(&mut zip, path, CONFIG_ENTRY, to_json(&redacted_config(config), path)?.as_bytes())?;
+    if let Ok(read_dir) = fs::read_dir(logs_dir()) {
+        for entry in read_dir.flatten() {
+            if let Ok(data) = log_tail(&entry.path(), MAX_LOG_BYTES) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                add_file(&mut zip, path, &format!("{LOGS_PREFIX}{name}"), &data)?;
+            }
+        }
+    }
+    zip.finish().map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn run_diagnostics(app: AppHandle) -> DiagnosticsReport {
+    collect(&app, &load_config())
+}
+
+/// Runs the checks and saves a support bundle to `path`.
+#[tauri::command]
+pub fn export_support_bundle(app: AppHandle, path: String) -> Result<DiagnosticsReport, AppError> {
+    let config = load_config();
+    let report = collect(&app, &config);
+    write_bundle(Path::new(&path), &report, &config)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::update_config;
+    use crate::test_support::with_test_home;
+    use zip::ZipArchive;
+
+    #[test]
+    fn checks_config_and_wallpaper_files() {
+        with_test_home(|home| {
+            let dir = get_config_path();
+            assert_eq!(check_config_dir(&dir).status, CheckStatus::Ok);
+            assert!(!dir.join(".write-test").exists());
+            assert_eq!(check_config_dir(&home.join("missing")).status, CheckStatus::Error);
+
+            let config = dir.join("config.json");
+            assert_eq!(check_config_file(&config, None).status, CheckStatus::Ok);
+            fs::write(&config, "{ \"shortcut\": ").unwrap();
+            let check = check_config_file(&config, None);
+            assert_eq!(check.status, CheckStatus::Error);
+            assert!(check.detail.starts_with("line 1"));
+
+            // A corrupt file moved aside at startup is still reported.
+            fs::remove_file(&config).unwrap();
+            let load_error = ConfigLoadError {
+                message: "EOF while parsing".into(),
+                line: 3,
+                column: 7,
+                backup_path: Some("/tmp/config.json.corrupt".into()),
+            };
+            let check = check_config_file(&config, Some(load_error));
+            assert_eq!(check.status, CheckStatus::Error);
+            assert_eq!(
+                check.detail,
+                "line 3, column 7: EOF while parsing; the original was moved to /tmp/config.json.corrupt"
+            );
+
+            let image = home.join("bg.png");
+            fs::write(&image, b"png").unwrap();
+            assert_eq!(check_wallpaper(None).status, CheckStatus::Ok);
+            assert_eq!(check_wallpaper(Some("https://example.com/bg.png")).status, CheckStatus::Ok);
+            assert_eq!(check_wallpaper(image.to_str()).status, CheckStatus::Ok);
+            let check = check_wallpaper(Some("file:///nowhere/bg.png"));
+            assert_eq!(check.status, CheckStatus::Error);
+            assert_eq!(check.detail, "/nowhere/bg.png does not exist");
+        });
+    }
+
+    #[test]
+    fn measures_icon_cache() {
+        with_test_home(|_| {
+            let cache = get_cache_dir();
+            fs::write(cache.join("a.png"), [0u8; 100]).unwrap();
+            fs::write(cache.join("b.png"), [0u8; 50]).unwrap();
+            assert_eq!(dir_size(&cache), (2, 150));
+            assert_eq!(check_icon_cache(2, 150).status, CheckStatus::Ok);
+            assert_eq!(check_icon_cache(9, ICON_CACHE_WARN_BYTES + 1).status, CheckStatus::Warning);
+        });
+    }
+
+    #[test]
+    fn bundles_report_config_and_logs() {
+        with_test_home(|home| {
+            update_config(|c| {
+                c.shortcut = "Cmd+J".into();
+                c.sync_folder = Some("/Users/me/Dropbox/launcher".into());
+                c.usage_counts.insert("/Applications/Slack.app".into(), 4);
+                c.file_search_roots = vec!["/Users/me/Clients".into()];
+                c.scripts.push(crate::config::ScriptAction {
+                    name: "deploy".into(),
+                    command: "TOKEN=secret ./deploy.sh".into(),
+                    cwd: None,
+                });
+            })
+            .unwrap();
+            fs::write(logs_dir().join("app.2026-03-01.log"), "{}\n").unwrap();
+            let old = "old line\n".repeat(MAX_LOG_BYTES as usize / 9 + 1);
+            fs::write(logs_dir().join("app.2026-03-02.log"), old + "newest line\n").unwrap();
+            let report = DiagnosticsReport {
+                generated_at: "now".into(),
+                app_version: "0.1.0".into(),
+                os: "macos aarch64".into(),
+                checks: vec![check_wallpaper(None).localized(Locale::En)],
+                icon_cache_files: 0,
+                icon_cache_bytes: 0,
+            };
+            let bundle = home.join("support.zip");
+            write_bundle(&bundle, &report, &load_config()).unwrap();
+
+            let mut zip = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+            let mut names: Vec<_> = zip.file_names().map(str::to_string).collect();
+            names.sort();
+            assert_eq!(
+                names,
+                ["config.json", "logs/app.2026-03-01.log", "logs/app.2026-03-02.log", "report.json"]
+            );
+            let mut log = String::new();
+            zip.by_name("logs/app.2026-03-02.log").unwrap().read_to_string(&mut log).unwrap();
+            assert!(log.len() as u64 <= MAX_LOG_BYTES);
+            assert!(log.starts_with("old line\n"));
+            assert!(log.ends_with("newest line\n"));
+            let report: serde_json::Value = serde_json::from_reader(zip.by_name(REPORT_ENTRY).unwrap()).unwrap();
+            assert_eq!(report["checks"][0]["id"], "wallpaper");
+            assert_eq!(report["checks"][0]["title"], "Wallpaper");
+            assert_eq!(report["checks"][0]["status"], "ok");
+            let config: serde_json::Value = serde_json::from_reader(zip.by_name(CONFIG_ENTRY).unwrap()).unwrap();
+            assert_eq!(config["shortcut"], "Cmd+J");
+            assert_eq!(config["scripts"][0]["name"], "deploy");
+            assert_eq!(config["scripts"][0]["command"], REDACTED);
+            assert!(config["scripts"][0]["cwd"].is_null());
+            assert_eq!(config["sync_folder"], REDACTED);
+            assert_eq!(config["usage_counts"], REDACTED);
+            assert_eq!(config["file_search_roots"], serde_json::json!([REDACTED]));
+        });
+    }
+}
@@ -0,0 +1,366 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::Path;
+
+use crate::config_patch::FieldError;
+use crate::i18n::{self, Locale};
+
+/// What a `NotFound`, `AlreadyExists` or `InvalidName` error is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    App,
+    File,
+    Folder,
+    Category,
+    Profile,
+    Backup,
+    Plugin,
+    Wallpaper,
+}
+
+impl Resource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::File => "file",
+            Self::Folder => "folder",
+            Self::Category => "category",
+            Self::Profile => "profile",
+            Self::Backup => "backup",
+            Self::Plugin => "plugin",
+            Self::Wallpaper => "wallpaper",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoAction {
+    Read,
+    Write,
+    Copy,
+    Delete,
+}
+
+impl IoAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Copy => "copy",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Every way a command can fail. Sent to the frontend as
+/// `{ code, message, context }`: `code` is stable for matching, `context`
+/// holds the values the message was built from so it can be re-rendered in
+/// another language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    NotFound { resource: Resource, name: String },
+    AlreadyExists { resource: Resource, name: String },
+    InvalidName { resource: Resource, name: String },
+    BuiltInCategory { name: String },
+    /// Moving or merging a category into itself or its own subtree.
+    CategoryCycle { from: String, to: String },
+    ActiveProfile { name: String },
+    InvalidConfig { source: String, detail: String },
+    InvalidArchive { detail: String },
+    InvalidPattern { pattern: String, detail: String },
+    InvalidDate { value: String },
+    InvalidRange { from: String, to: String },
+    InvalidLogLevel { value: String },
+    UnsupportedFormat { path: String },
+    /// A file the app did not create and so will not delete.
+    NotManaged { path: String },
+    InvalidFields { errors: Vec<FieldError> },
+    StaleConfig { revision: u64, current: u64 },
+    NothingToUndo,
+    NothingToRedo,
+    /// The list `field` changed again after the change `label` was made, so
+    /// undoing or redoing it would also revert the later edit.
+    HistoryConflict { label: String, field: String },
+    /// `action` is a `SystemAction` id such as "shut_down".
+    ConfirmationRequired { action: String },
+    CommandFailed { program: String, detail: String },
+    Io { action: IoAction, path: String, detail: String },
+    Plugin { detail: String },
+}
+
+impl AppError {
+    pub fn not_found(resource: Resource, name: impl Into<String>) -> Self {
+        Self::NotFound {
+            resource,
+            name: name.into(),
+        }
+    }
+
+    pub fn io(action: IoAction, path: impl AsRef<Path>, error: impl fmt::Display) -> Self {
+        Self::Io {
+            action,
+            path: path.as_ref().display().to_string(),
+            detail: error.to_string(),
+        }
+    }
+
+    pub fn command(program: impl Into<String>, error: impl fmt::Display) -> Self {
+        Self::CommandFailed {
+            program: program.into(),
+            detail: error.to_string(),
+        }
+    }
+
+    pub fn plugin(error: impl fmt::Display) -> Self {
+        Self::Plugin {
+            detail: error.to_string(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "not_found",
+            Self::AlreadyExists { .. } => "already_exists",
+            Self::InvalidName { .. } => "invalid_name",
+            Self::BuiltInCategory { .. } => "built_in_category",
+            Self::CategoryCycle { .. } => "category_cycle",
+            Self::ActiveProfile { .. } => "active_profile",
+            Self::InvalidConfig { .. } => "invalid_config",
+            Self::InvalidArchive { .. } => "invalid_archive",
+            Self::InvalidPattern { .. } => "invalid_pattern",
+            Self::InvalidDate { .. } => "invalid_date",
+            Self::InvalidRange { .. } => "invalid_range",
+            Self::InvalidLogLevel { .. } => "invalid_log_level",
+            Self::UnsupportedFormat { .. } => "unsupported_format",
+            Self::NotManaged { .. } => "not_managed",
+            Self::InvalidFields { .. } => "invalid_fields",
+            Self::StaleConfig { .. } => "stale_config",
+            Self::NothingToUndo => "nothing_to_undo",
+            Self::NothingToRedo => "nothing_to_redo",
+            Self::HistoryConflict { .. } => "history_conflict",
+            Self::ConfirmationRequired { .. } => "confirmation_required",
+            Self::CommandFailed { .. } => "command_failed",
+            Self::Io { .. } => "io",
+            Self::Plugin { .. } => "plugin",
+        }
+    }
+
+    pub fn context(&self) -> Map<String, Value> {
+        let s = |v: &str| Value::String(v.to_string());
+        let fields: Vec<(&str, Value)> = match self {
+            Self::NotFound { resource, name }
+            | Self::AlreadyExists { resource, name }
+            | Self::InvalidName { resource, name } => vec![("resource", s(resource.as_str())), ("name", s(name))],
+            Self::BuiltInCategory { name } | Self::ActiveProfile { name } => vec![("name", s(name))],
+            Self::CategoryCycle { from, to } | Self::InvalidRange { from, to } => {
+                vec![("from", s(from)), ("to", s(to))]
+            }
+            Self::InvalidConfig { source, detail } => vec![("source", s(source)), ("detail", s(detail))],
+            Self::InvalidArchive { detail } | Self::Plugin { detail } => vec![("detail", s(detail))],
+            Self::InvalidPattern { pattern, detail } => vec![("pattern", s(pattern)), ("detail", s(detail))],
+            Self::InvalidDate { value } | Self::InvalidLogLevel { value } => vec![("value", s(value))],
+            Self::UnsupportedFormat { path } | Self::NotManaged { path } => vec![("path", s(path))],
+            Self::InvalidFields { errors } => vec![
+                (
+                    "fields",
+                    s(&errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>().join(", ")),
+                ),
+                ("errors", serde_json::to_value(errors).unwrap_or_default()),
+            ],
+            Self::StaleConfig { revision, current } => {
+                vec![("revision", Value::from(*revision)), ("current", Value::from(*current))]
+            }
+            Self::NothingToUndo | Self::NothingToRedo => vec![],
+            Self::HistoryConflict { label, field } => vec![("label", s(label)), ("field", s(field))],
+            Self::ConfirmationRequired { action } => vec![("action", s(action))],
+            Self::CommandFailed { program, detail } => vec![("program", s(program)), ("detail", s(detail))],
+            Self::Io { action, path, detail } => {
+                vec![("action", s(action.as_str())), ("path", s(path)), ("detail", s(detail))]
+            }
+        };
+        fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
+
+    pub fn message(&self) -> String {
+        self.message_in(i18n::current())
+    }
+
+    /// The message in `locale`, with resource and action names translated too.
+    pub(crate) fn message_in(&self, locale: Locale) -> String {
+        let mut context = self.context();
+        for (key, prefix) in [("resource", "resource"), ("action", self.action_prefix())] {
+            if let Some(Value::String(value)) = context.get_mut(key) {
+                *value = i18n::tr(locale, &format!("{}.{}", prefix, value)).to_string();
+            }
+        }
+        render(i18n::tr(locale, &format!("error.{}", self.code())), &context)
+    }
+
+    fn action_prefix(&self) -> &'static str {
+        match self {
+            Self::ConfirmationRequired { .. } => "action",
+            _ => "io",
+        }
+    }
+}
+
+/// Replaces each `{key}` in `template` with that context value in a single
+/// pass, so braces inside substituted values are left alone. Placeholders
+/// without a value are kept as written.
+pub(crate) fn render(template: &str, context: &Map<String, Value>) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        match context.get(&after[..end]) {
+            Some(Value::String(value)) => text.push_str(value),
+            Some(other) => text.push_str(&other.to_string()),
+            None => text.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    text.push_str(rest);
+    text
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::update_config;
+    use crate::test_support::with_test_home;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_code_message_and_context() {
+        with_test_home(|_| {
+            update_config(|c| c.locale = "en".into()).unwrap();
+            let error = AppError::not_found(Resource::Profile, "Work");
+            assert_eq!(
+                serde_json::to_value(&error).unwrap(),
+                json!({
+                    "code": "not_found",
+                    "message": "Could not find profile \"Work\"",
+                    "context": {"resource": "profile", "name": "Work"}
+                })
+            );
+
+            let error = AppError::StaleConfig { revision: 3, current: 5 };
+            assert_eq!(error.context()["current"], json!(5));
+            assert!(error.to_string().contains("revision 3 is not 5"));
+
+            update_config(|c| c.locale = "zh-CN".into()).unwrap();
+            assert_eq!(AppError::NothingToUndo.to_string(), "没有可撤销的操作");
+        });
+    }
+
+    #[test]
+    fn translates_resources_and_actions() {
+        let error = AppError::not_found(Resource::Profile, "Work");
+        assert_eq!(error.message_in(Locale::ZhCn), "找不到配置方案“Work”");
+        let error = AppError::io(IoAction::Delete, "/tmp/x", "denied");
+        assert_eq!(error.message_in(Locale::En), "Failed to delete /tmp/x: denied");
+        let error = AppError::ConfirmationRequired {
+            action: "shut_down".into(),
+        };
+        assert_eq!(error.message_in(Locale::En), "Shut Down requires confirmation");
+        assert_eq!(error.context()["action"], json!("shut_down"));
+    }
+
+    #[test]
+    fn renders_values_without_expanding_them() {
+        let context = Map::from_iter([
+            ("from".to_string(), json!("{to}")),
+            ("to".to_string(), json!("Dev")),
+            ("count".to_string(), json!(2)),
+        ]);
+        assert_eq!(render("{from} -> {to} ({count})", &context), "{to} -> Dev (2)");
+        assert_eq!(render("{missing} and {to", &context), "{missing} and {to");
+    }
+
+    #[test]
+    fn every_placeholder_is_filled() {
+        let errors = [
+            AppError::not_found(Resource::App, "/A.app"),
+            AppError::AlreadyExists {
+                resource: Resource::Category,
+                name: "Dev".into(),
+            },
+            AppError::CategoryCycle {
+                from: "A".into(),
+                to: "A/B".into(),
+            },
+            AppError::io(IoAction::Write, "/tmp/x", "denied"),
+            AppError::command("open", "exit status: 1"),
+            AppError::InvalidFields {
+                errors: vec![FieldError {
+                    field: "theme".into(),
+                    code: "unknown_theme".into(),
+                    message: "unknown".into(),
+                }],
+            },
+            AppError::InvalidConfig {
+                source: "backup".into(),
+                detail: "EOF".into(),
+            },
+            AppError::ConfirmationRequired { action: "sleep".into() },
+            AppError::InvalidName {
+                resource: Resource::Profile,
+                name: "../x".into(),
+            },
+            AppError::BuiltInCategory { name: "System".into() },
+            AppError::ActiveProfile { name: "Work".into() },
+            AppError::InvalidArchive { detail: "EOF".into() },
+            AppError::InvalidPattern {
+                pattern: "(".into(),
+                detail: "unclosed group".into(),
+            },
+            AppError::InvalidDate { value: "May".into() },
+            AppError::InvalidRange {
+                from: "2026-03-02".into(),
+                to: "2026-03-01".into(),
+            },
+            AppError::InvalidLogLevel { value: "loud".into() },
+            AppError::UnsupportedFormat { path: "/a.bmp".into() },
+            AppError::NotManaged { path: "/a.png".into() },
+            AppError::StaleConfig { revision: 1, current: 2 },
+            AppError::NothingToUndo,
+            AppError::NothingToRedo,
+            AppError::HistoryConflict {
+                label: "Added script".into(),
+                field: "scripts".into(),
+            },
+            AppError::plugin("trap"),
+        ];
+        for error in errors {
+            for locale in [Locale::En, Locale::ZhCn] {
+                let message = error.message_in(locale);
+                assert!(!message.is_empty(), "no {:?} text for {}", locale, error.code());
+                assert!(!message.contains('{'), "{}: {}", error.code(), message);
+            }
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use ignore::WalkBuilder;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::config::{expand_home, load_config, update_config_labeled};
+use crate::error::{AppError, Resource};
+
+const DEFAULT_LIMIT: usize = 50;
+
+static FILE_INDEX: Lazy<RwLock<FileIndex>> = Lazy::new(|| RwLock::new(FileIndex::default()));
+static FILE_WATCHER: Lazy<Mutex<Option<RecommendedWatcher>>> = Lazy::new(|| Mutex::new(None));
+/// Bumped by every `start_file_index`; only the latest rebuild is installed.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileResult {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+}
+
+/// File-name index over the configured search roots, keyed by path so a
+/// changed directory's children can be replaced without a full rescan.
+#[derive(Default)]
+pub(crate) struct FileIndex {
+    roots: Vec<PathBuf>,
+    ignore: Vec<String>,
+    entries: BTreeMap<PathBuf, FileResult>,
+}
+
+impl FileIndex {
+    pub(crate) fn new(roots: Vec<PathBuf>, ignore: Vec<String>) -> Self {
+        Self {
+            roots,
+            ignore,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn walker(&self, dir: &Path, max_depth: Option<usize>) -> ignore::Walk {
+        let ignored = self.ignore.clone();
+        WalkBuilder::new(dir)
+            .max_depth(max_depth)
+            .require_git(false)
+            .filter_entry(move |e| {
+                let name = e.file_name().to_string_lossy();
+                !ignored.iter().any(|i| *i == name)
+            })
+            .build()
+    }
+
+    fn index_dir(&mut self, dir: &Path, max_depth: Option<usize>) {
+        let walk = self.walker(dir, max_depth);
+        for entry in walk.flatten() {
+            // The walk root is either a search root or already indexed by its parent.
+            if entry.depth() == 0 {
+                continue;
+            }
+            let path = entry.path().to_path_buf();
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            self.entries.insert(
+                path.clone(),
+                FileResult {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    is_dir,
+                },
+            );
+        }
+    }
+
+    pub(crate) fn rebuild(&mut self) {
+        self.entries.clear();
+        for root in self.roots.clone() {
+            if root.is_dir() {
+                self.index_dir(&root, None);
+            }
+        }
+    }
+
+    /// Indexed paths strictly below `dir`. Keys sort component-wise, so
+    /// they sit right after `dir` in the map.
+    fn below<'a>(&'a self, dir: &'a Path) -> impl Iterator<Item = &'a PathBuf> + 'a {
+        self.entries
+            .range::<Path, _>((Bound::Excluded(dir), Bound::Unbounded))
+            .map(|(path, _)| path)
+            .take_while(move |path| path.starts_with(dir))
+    }
+
+    /// Drops `path` and everything indexed below it.
+    fn remove_tree(&mut self, path: &Path) {
+        let below: Vec<PathBuf> = self.below(path).cloned().collect();
+        for p in below {
+            self.entries.remove(&p);
+        }
+        self.entries.remove(path);
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        path.components().any(|c| {
+            let name = c.as_os_str().to_string_lossy();
+            self.ignore.iter().any(|i| *i == name)
+        })
+    }
+
+    /// Re-reads the directories affected by changes to `paths`. Only the
+    /// direct children of each directory are rescanned; subdirectories that
+    /// were not indexed before are walked in full.
+    pub(crate) fn refresh_paths(&mut self, paths: &[PathBuf]) {
+        let mut dirs: Vec<PathBuf> = paths
+            .iter()
+            .filter_map(|p| {
+                if p.is_dir() && self.roots.iter().any(|r| r == p) {
+                    Some(p.clone())
+                } else {
+                    p.parent().map(Path::to_path_buf)
+                }
+            })
+            .filter(|d| self.roots.iter().any(|r| d.starts_with(r)))
+            .filter(|d| !self.is_ignored(d))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        for dir in dirs {
+            let previous: Vec<PathBuf> = self
+                .below(&dir)
+                .filter(|p| p.parent() == Some(dir.as_path()))
+                .cloned()
+                .collect();
+            for path in &previous {
+                if !path.exists() {
+                    self.remove_tree(path);
+                }
+            }
+            if !dir.is_dir() {
+                continue;
+            }
+
+            let before: Vec<PathBuf> = previous.into_iter().filter(|p| p.exists()).collect();
+            for path in &before {
+                self.entries.remove(path);
+            }
+            self.index_dir(&dir, Some(1));
+
+            let new_dirs: Vec<PathBuf> = self
+                .below(&dir)
+                .filter(|p| p.parent() == Some(dir.as_path()) && !before.contains(p))
+                .filter(|p| self.entries.get(*p).is_some_and(|e| e.is_dir))
+                .cloned()
+                .collect();
+            for sub in new_dirs {
+                self.index_dir(&sub, None);
+            }
+        }
+    }
+
+    pub(crate) fn search(&self, query: &str, limit: usize) -> Vec<FileResult> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+        let mut matches: Vec<(u8, &FileResult)> = self
+            .entries
+            .values()
+            .filter_map(|e| {
+                let name = e.name.to_lowercase();
+                if name == query {
+                    Some((0, e))
+                } else if name.starts_with(&query) {
+                    Some((1, e))
+                } else if name.contains(&query) {
+                    Some((2, e))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.path.len().cmp(&b.1.path.len())));
+        matches.into_iter().take(limit).map(|(_, e)| e.clone()).collect()
+    }
+}
+
+fn configured_index() -> FileIndex {
+    let config = load_config();
+    let roots = config
+        .file_search_roots
+        .iter()
+        .map(|r| expand_home(r))
+        .collect();
+    FileIndex::new(roots, config.file_search_ignore.clone())
+}
+
+/// Builds the index in the background and keeps it current with a
+/// filesystem watcher on every root. The watcher starts before the walk and
+/// its events are replayed once the index is in place. When called again
+/// before a rebuild finishes, only the latest one is installed.
+pub(crate) fn start_file_index() {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        let mut index = configured_index();
+        // Paths changed during the walk; `None` once the index is installed.
+        let pending: Arc<Mutex<Option<Vec<PathBuf>>>> = Arc::new(Mutex::new(Some(vec![])));
+        let events = Arc::clone(&pending);
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if GENERATION.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let mut pending = events.lock();
+            match pending.as_mut() {
+                Some(paths) => paths.extend(event.paths),
+                None => FILE_INDEX.write().refresh_paths(&event.paths),
+            }
+        });
+        let watcher = match watcher {
+            Ok(mut watcher) => {
+                for root in index.roots.iter().filter(|r| r.is_dir()) {
+                    if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+                        tracing::warn!(root = %root.display(), "could not watch file search root: {}", e);
+                    }
+                }
+                Some(watcher)
+            }
+            Err(e) => {
+                tracing::error!("could not start the file search watcher: {}", e);
+                None
+            }
+        };
+        index.rebuild();
+
+        let mut installed = FILE_WATCHER.lock();
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let mut pending = pending.lock();
+        if let Some(paths) = pending.take() {
+            index.refresh_paths(&paths);
+        }
+        *FILE_INDEX.write() = index;
+        *installed = watcher;
+    });
+}
+
+#[tauri::command]
+pub fn search_files(query: String, limit: Option<usize>) -> Vec<FileResult> {
+    FILE_INDEX.read().search(&query, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+#[tauri::command]
+pub fn update_file_search_settings(roots: Vec<String>, ignore: Vec<String>) -> Result<(), AppError> {
+    update_config_labeled("Changed file search settings", |config| {
+        config.file_search_roots = roots;
+        config.file_search_ignore = ignore;
+    })?;
+    start_file_index();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn open_file(path: String) -> Result<(), AppError> {
+    if !Path::new(&path).exists() {
+        return Err(AppError::not_found(Resource::File, path));
+    }
+    Command::new("open")
+        .arg(&path)
+        .spawn()
+        .map_err(|e| AppError::command("open", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn copy_path(path: String) -> Result<(), AppError> {
+    let mut child = Command::new("pbcopy")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::command("pbcopy", e))?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin
+            .write_all(path.as_bytes())
+            .map_err(|e| AppError::command("pbcopy", e))?;
+    }
+    let status = child.wait().map_err(|e| AppError::command("pbcopy", e))?;
+    if !status.success() {
+        return Err(AppError::command("pbcopy", status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::test_support::temp_dir;
+
+    fn names(results: &[FileResult]) -> Vec<String> {
+        results.iter().map(|r| r.name.clone()).collect()
+    }
+
+    #[test]
+    fn indexes_roots_with_ignore_rules() {
+        let root = temp_dir("files_ignore");
+        fs::create_dir_all(root.join("project/node_modules/dep")).unwrap();
+        fs::create_dir_all(root.join("project/target/debug")).unwrap();
+        fs::create_dir_all(root.join("project/src")).unwrap();
+        fs::write(root.join("project/.gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("project/src/report.md"), "").unwrap();
+        fs::write(root.join("project/build.log"), "").unwrap();
+        fs::write(root.join("project/node_modules/dep/report.js"), "").unwrap();
+        fs::write(root.join("project/target/debug/report.bin"), "").unwrap();
+
+        let mut index = FileIndex::new(
+            vec![root.to_path_buf()],
+            vec!["node_modules".to_string(), "target".to_string()],
+        );
+        index.rebuild();
+
+        assert_eq!(names(&index.search("report", 10)), vec!["report.md"]);
+        assert!(index.search("build.log", 10).is_empty());
+        assert_eq!(names(&index.search("project", 10)), vec!["project"]);
+    }
+
+    #[test]
+    fn ranks_exact_then_prefix_then_substring() {
+        let root = temp_dir("files_rank");
+        fs::write(root.join("my-notes.txt"), "").unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+        fs::write(root.join("notes"), "").unwrap();
+
+        let mut index = FileIndex::new(vec![root.to_path_buf()], vec![]);
+        index.rebuild();
+
+        assert_eq!(
+            names(&index.search("Notes", 10)),
+            vec!["notes", "notes.txt", "my-notes.txt"]
+        );
+        assert_eq!(index.search("notes", 1).len(), 1);
+        assert!(index.search("  ", 10).is_empty());
+    }
+
+    #[test]
+    fn refreshes_changed_paths_incrementally() {
+        let root = temp_dir("files_refresh");
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/old.txt"), "").unwrap();
+
+        let mut index = FileIndex::new(vec![root.to_path_buf()], vec!["target".to_string()]);
+        index.rebuild();
+        assert_eq!(index.search("old", 10).len(), 1);
+
+        fs::remove_file(root.join("docs/old.txt")).unwrap();
+        fs::write(root.join("docs/new.txt"), "").unwrap();
+        fs::create_dir_all(root.join("docs/nested/deeper")).unwrap();
+        fs::write(root.join("docs/nested/deeper/inner.txt"), "").unwrap();
+        fs::create_dir_all(root.join("docs/target")).unwrap();
+        fs::write(root.join("docs/target/artifact.txt"), "").unwrap();
+        index.refresh_paths(&[
+            root.join("docs/old.txt"),
+            root.join("docs/new.txt"),
+            root.join("docs/nested"),
+            root.join("docs/target/artifact.txt"),
+        ]);
+
+        assert!(index.search("old", 10).is_empty());
+        assert_eq!(names(&index.search("new", 10)), vec!["new.txt"]);
+        assert_eq!(names(&index.search("inner", 10)), vec!["inner.txt"]);
+        assert!(index.search("artifact", 10).is_empty());
+
+        // A sibling sharing the name as a prefix stays indexed.
+        fs::create_dir_all(root.join("docs/nested-copy")).unwrap();
+        fs::write(root.join("docs/nested-copy/inner.txt"), "").unwrap();
+        index.refresh_paths(&[root.join("docs/nested-copy")]);
+        fs::remove_dir_all(root.join("docs/nested")).unwrap();
+        index.refresh_paths(&[root.join("docs/nested")]);
+        assert_eq!(index.search("inner", 10).len(), 1);
+        assert!(index.search("deeper", 10).is_empty());
+    }
+}
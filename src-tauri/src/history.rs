@@ -0,0 +1,305 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use tauri::AppHandle;
+
+use crate::config::{update_config, AppConfig};
+use crate::error::AppError;
+
+const MAX_HISTORY: usize = 50;
+
+struct Entry {
+    label: String,
+    before: AppConfig,
+    after: AppConfig,
+}
+
+#[derive(Default)]
+struct History {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+static HISTORY: Lazy<Mutex<History>> = Lazy::new(|| Mutex::new(History::default()));
+
+/// Labels of the changes `undo` and `redo` would revert or reapply next.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct HistoryState {
+    pub undo: Option<String>,
+    pub redo: Option<String>,
+}
+
+/// Records a labelled change. A new change discards everything that could
+/// be redone.
+pub(crate) fn record(label: String, before: AppConfig, after: AppConfig) {
+    let mut history = HISTORY.lock();
+    history.redo.clear();
+    history.undo.push(Entry { label, before, after });
+    if history.undo.len() > MAX_HISTORY {
+        history.undo.remove(0);
+    }
+}
+
+pub(crate) fn clear() {
+    *HISTORY.lock() = History::default();
+}
+
+pub(crate) fn state() -> HistoryState {
+    let history = HISTORY.lock();
+    HistoryState {
+        undo: history.undo.last().map(|e| e.label.clone()),
+        redo: history.redo.last().map(|e| e.label.clone()),
+    }
+}
+
+/// Applies the change from `from` to `to` onto `current`, key by key within
+/// objects, so values the change didn't touch keep their current state.
+/// Lists are replaced whole, so one that changed again since `to` was
+/// recorded is a conflict; the error names the top-level key it is under.
+fn apply_diff(current: &mut Value, from: &Value, to: &Value) -> Result<(), Option<String>> {
+    let (Value::Object(current), Value::Object(from), Value::Object(to)) = (&mut *current, from, to) else {
+        if from != to {
+            if from.is_array() && current != from {
+                return Err(None);
+            }
+            *current = to.clone();
+        }
+        return Ok(());
+    };
+    let keys: BTreeSet<&String> = from.keys().chain(to.keys()).collect();
+    for key in keys {
+        match (from.get(key), to.get(key)) {
+            (before, after) if before == after => {}
+            (_, None) => {
+                current.remove(key);
+            }
+            (Some(before), Some(after)) if current.contains_key(key) => {
+                apply_diff(&mut current[key], before, after).map_err(|_| Some(key.clone()))?;
+            }
+            (_, Some(after)) => {
+                current.insert(key.clone(), after.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_value(config: &AppConfig) -> Value {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    value["revision"] = Value::Null;
+    value
+}
+
+/// Moves the newest entry of one stack onto the other, reverting or
+/// reapplying exactly the values it changed. Changes made since then to
+/// other values (launch counts, other edits) are kept.
+fn step(is_undo: bool) -> Result<(String, AppConfig), AppError> {
+    let mut taken = None;
+    let stored = update_config(|current| {
+        let mut history = HISTORY.lock();
+        let stack = if is_undo { &mut history.undo } else { &mut history.redo };
+        let Some(entry) = stack.pop() else {
+            return Ok(());
+        };
+        let (from, to) = if is_undo {
+            (&entry.after, &entry.before)
+        } else {
+            (&entry.before, &entry.after)
+        };
+        let mut value = serde_json::to_value(&*current).unwrap_or_default();
+        if let Err(field) = apply_diff(&mut value, &to_value(from), &to_value(to)) {
+            // Reverting the whole list would also revert the later edit. The
+            // entry can never apply cleanly, so it is dropped.
+            let field = field.unwrap_or_default();
+            tracing::warn!(label = %entry.label, field = %field, "dropped conflicting history entry");
+            return Err(AppError::HistoryConflict {
+                label: entry.label,
+                field,
+            });
+        }
+        match serde_json::from_value(value) {
+            Ok(next) => *current = next,
+            Err(e) => tracing::error!(label = %entry.label, "could not apply history entry: {}", e),
+        }
+        taken = Some(entry);
+        Ok(())
+    });
+    if let Ok(Err(conflict)) = stored {
+        return Err(conflict);
+    }
+
+    let entry = taken.ok_or(if is_undo {
+        AppError::NothingToUndo
+    } else {
+        AppError::NothingToRedo
+    })?;
+    let label = entry.label.clone();
+    let history = &mut *HISTORY.lock();
+    let (source, target) = if is_undo {
+        (&mut history.undo, &mut history.redo)
+    } else {
+        (&mut history.redo, &mut history.undo)
+    };
+    if let Err(error) = stored {
+        tracing::warn!(label = %label, undo = is_undo, "could not apply history entry: {}", error);
+        // Nothing changed; leave the entry where it was.
+        source.push(entry);
+        return Err(error);
+    }
+    tracing::info!(label = %label, undo = is_undo, "applied history entry");
+    target.push(entry);
+    Ok((label, crate::config::load_config()))
+}
+
+pub(crate) fn undo_change() -> Result<(String, AppConfig), AppError> {
+    step(true)
+}
+
+pub(crate) fn redo_change() -> Result<(String, AppConfig), AppError> {
+    step(false)
+}
+
+#[tauri::command]
+pub fn undo(app: AppHandle) -> Result<String, AppError> {
+    let (label, config) = undo_change()?;
+    crate::apply_config(&app, &config);
+    Ok(label)
+}
+
+#[tauri::command]
+pub fn redo(app: AppHandle) -> Result<String, AppError> {
+    let (label, config) = redo_change()?;
+    crate::apply_config(&app, &config);
+    Ok(label)
+}
+
+#[tauri::command]
+pub fn get_history_state() -> HistoryState {
+    state()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{add_category, load_config, remove_category, update_app_category};
+    use crate::test_support::with_test_home;
+
+    #[test]
+    fn undo_restores_removed_category_and_assignments() {
+        with_test_home(|_| {
+            clear();
+            update_app_category("/Figma.app".into(), "Design".into()).unwrap();
+            remove_category("Design".into()).unwrap();
+            assert_eq!(
+                state(),
+                HistoryState {
+                    undo: Some("Removed category Design".into()),
+                    redo: None
+                }
+            );
+
+            // An unrelated change made afterwards survives the undo.
+            update_config(|c| {
+                c.usage_counts.insert("/Slack.app".into(), 3);
+            })
+            .unwrap();
+            let (label, config) = undo_change().unwrap();
+            assert_eq!(label, "Removed category Design");
+            assert!(config.user_categories.contains(&"Design".to_string()));
+            assert_eq!(config.categories["/Figma.app"], "Design");
+            assert_eq!(config.usage_counts["/Slack.app"], 3);
+
+            let (_, config) = redo_change().unwrap();
+            assert!(!config.user_categories.contains(&"Design".to_string()));
+            assert!(config.categories.is_empty());
+            assert!(state().redo.is_none());
+        });
+    }
+
+    #[test]
+    fn undo_restores_machine_specific_fields_exactly() {
+        with_test_home(|_| {
+            clear();
+            update_config(|c| {
+                c.usage_counts.insert("/Slack.app".into(), 5);
+            })
+            .unwrap();
+            crate::config::update_config_labeled("Settings", |c| {
+                c.log_level = "debug".into();
+                c.incognito = true;
+            })
+            .unwrap();
+            update_config(|c| {
+                c.usage_counts.insert("/Slack.app".into(), 2);
+            })
+            .unwrap();
+
+            let (_, config) = undo_change().unwrap();
+            assert_eq!(config.log_level, "info");
+            assert!(!config.incognito);
+            // Counts the entry didn't change keep their current value.
+            assert_eq!(config.usage_counts["/Slack.app"], 2);
+        });
+    }
+
+    #[test]
+    fn refuses_to_revert_a_list_changed_afterwards() {
+        with_test_home(|_| {
+            clear();
+            add_category("Games".into()).unwrap();
+            add_category("Music".into()).unwrap();
+            update_config(|c| c.user_categories.retain(|c| c != "Design")).unwrap();
+
+            let error = undo_change().unwrap_err();
+            assert_eq!(error.code(), "history_conflict");
+            assert_eq!(error.context()["field"], "user_categories");
+            let config = load_config();
+            assert!(config.user_categories.contains(&"Music".to_string()));
+            assert!(!config.user_categories.contains(&"Design".to_string()));
+            assert_eq!(state().undo.as_deref(), Some("Added category Games"));
+        });
+    }
+
+    #[test]
+    fn external_edits_clear_history() {
+        with_test_home(|_| {
+            clear();
+            add_category("Games".into()).unwrap();
+            assert!(state().undo.is_some());
+            let mut edited = load_config();
+            edited.theme = "Forest".into();
+            std::fs::write(
+                crate::config::config_file_path(),
+                serde_json::to_string_pretty(&edited).unwrap(),
+            )
+            .unwrap();
+            assert!(crate::config::reload_from_disk().unwrap().is_some());
+            assert!(state().undo.is_none());
+        });
+    }
+
+    #[test]
+    fn new_changes_clear_redo_and_history_is_bounded() {
+        with_test_home(|_| {
+            clear();
+            for i in 0..(MAX_HISTORY + 5) {
+                add_category(format!("Cat {}", i)).unwrap();
+            }
+            assert_eq!(HISTORY.lock().undo.len(), MAX_HISTORY);
+
+            undo_change().unwrap();
+            assert!(state().redo.is_some());
+            add_category("Other".into()).unwrap();
+            assert!(state().redo.is_none());
+            assert!(redo_change().is_err());
+
+            // Unchanged configs are not recorded.
+            add_category("Other".into()).unwrap();
+            assert_eq!(state().undo.as_deref(), Some("Added category Other"));
+            undo_change().unwrap();
+            assert!(!load_config().user_categories.contains(&"Other".to_string()));
+        });
+    }
+}
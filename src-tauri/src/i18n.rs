@@ -0,0 +1,237 @@
+use crate::config::{load_config, AppConfig};
+
+/// Values accepted for `AppConfig::locale`; "system" follows the OS.
+pub(crate) const LOCALE_SETTINGS: [&str; 3] = ["system", "en", "zh-CN"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::En => EN,
+            Self::ZhCn => ZH_CN,
+        }
+    }
+}
+
+/// The locale for a `locale` setting, using `system` (a BCP 47 tag such as
+/// "zh-Hans-CN") when the setting is "system" or unknown.
+pub(crate) fn resolve(setting: &str, system: Option<&str>) -> Locale {
+    let tag = match setting {
+        "en" | "zh-CN" => setting,
+        _ => system.unwrap_or("en"),
+    };
+    if tag.to_ascii_lowercase().starts_with("zh") {
+        Locale::ZhCn
+    } else {
+        Locale::En
+    }
+}
+
+pub(crate) fn current() -> Locale {
+    for_config(&load_config())
+}
+
+/// The locale `config` asks for, without reloading it.
+pub(crate) fn for_config(config: &AppConfig) -> Locale {
+    resolve(&config.locale, sys_locale::get_locale().as_deref())
+}
+
+/// `key` in `locale`, falling back to English.
+pub(crate) fn tr(locale: Locale, key: &str) -> &'static str {
+    let find = |locale: Locale| locale.catalog().iter().find(|(k, _)| *k == key).map(|(_, text)| *text);
+    find(locale).or_else(|| find(Locale::En)).unwrap_or("")
+}
+
+const EN: &[(&str, &str)] = &[
+    ("tray.show", "Show app"),
+    ("tray.profiles", "Profiles"),
+    ("tray.incognito", "Incognito"),
+    ("tray.undo", "Undo"),
+    ("tray.redo", "Redo"),
+    ("tray.quit", "Quit"),
+    ("action.lock_screen", "Lock Screen"),
+    ("action.sleep", "Sleep"),
+    ("action.restart", "Restart"),
+    ("action.shut_down", "Shut Down"),
+    ("action.log_out", "Log Out"),
+    ("action.empty_trash", "Empty Trash"),
+    ("action.toggle_dark_mode", "Toggle Dark Mode"),
+    ("action.eject_all_disks", "Eject All Disks"),
+    ("action.show_desktop", "Show Desktop"),
+    ("diagnostics.spotlight", "Spotlight"),
+    ("diagnostics.swift", "Swift (app icons)"),
+    ("diagnostics.config_dir", "Config folder"),
+    ("diagnostics.config_file", "Config file"),
+    ("diagnostics.shortcut", "Global shortcut"),
+    ("diagnostics.wallpaper", "Wallpaper"),
+    ("diagnostics.icon_cache", "Icon cache"),
+    ("resource.app", "app"),
+    ("resource.file", "file"),
+    ("resource.folder", "folder"),
+    ("resource.category", "category"),
+    ("resource.profile", "profile"),
+    ("resource.backup", "backup"),
+    ("resource.plugin", "plugin"),
+    ("resource.wallpaper", "wallpaper"),
+    ("io.read", "read"),
+    ("io.write", "write"),
+    ("io.copy", "copy"),
+    ("io.delete", "delete"),
+    ("field.not_an_object", "Settings must be a JSON object"),
+    ("field.read_only", "is read-only"),
+    ("field.unknown_field", "unknown setting"),
+    ("field.invalid_value", "invalid value: {detail}"),
+    ("field.between", "must be between {min} and {max}"),
+    ("field.one_of", "must be one of {values}"),
+    ("field.unknown_theme", "unknown theme \"{name}\""),
+    ("field.empty", "must not be empty"),
+    ("field.at_least", "must be at least {min}"),
+    ("field.unknown_category", "unknown category \"{name}\""),
+    ("field.invalid_category", "\"{name}\" is not a valid category name"),
+    ("field.duplicate", "lists \"{name}\" more than once"),
+    ("error.not_found", "Could not find {resource} \"{name}\""),
+    ("error.already_exists", "A {resource} named \"{name}\" already exists"),
+    ("error.invalid_name", "\"{name}\" is not a valid {resource} name"),
+    ("error.built_in_category", "\"{name}\" is a built-in category"),
+    ("error.category_cycle", "Cannot move \"{from}\" into \"{to}\""),
+    ("error.active_profile", "Cannot delete the active profile \"{name}\""),
+    ("error.invalid_config", "{source} is not a valid config: {detail}"),
+    ("error.invalid_archive", "Invalid profile archive: {detail}"),
+    ("error.invalid_pattern", "Invalid pattern \"{pattern}\": {detail}"),
+    ("error.invalid_date", "Invalid date \"{value}\""),
+    ("error.invalid_range", "Range starts after it ends ({from} to {to})"),
+    ("error.invalid_log_level", "Unknown log level \"{value}\""),
+    (
+        "error.unsupported_format",
+        "Unsupported image format (PNG, JPG, JPEG, WebP and GIF are supported)",
+    ),
+    ("error.not_managed", "Only wallpapers saved by the app can be deleted"),
+    ("error.invalid_fields", "Invalid settings: {fields}"),
+    (
+        "error.stale_config",
+        "Config changed since it was loaded (revision {revision} is not {current}); reload and try again",
+    ),
+    ("error.nothing_to_undo", "Nothing to undo"),
+    ("error.nothing_to_redo", "Nothing to redo"),
+    (
+        "error.history_conflict",
+        "Cannot undo or redo \"{label}\": {field} was changed afterwards, so the change was dropped from history",
+    ),
+    ("error.confirmation_required", "{action} requires confirmation"),
+    ("error.command_failed", "{program} failed: {detail}"),
+    ("error.io", "Failed to {action} {path}: {detail}"),
+    ("error.plugin", "Plugin error: {detail}"),
+];
+
+const ZH_CN: &[(&str, &str)] = &[
+    ("tray.show", "显示应用"),
+    ("tray.profiles", "配置方案"),
+    ("tray.incognito", "无痕模式"),
+    ("tray.undo", "撤销"),
+    ("tray.redo", "重做"),
+    ("tray.quit", "退出"),
+    ("action.lock_screen", "锁定屏幕"),
+    ("action.sleep", "睡眠"),
+    ("action.restart", "重新启动"),
+    ("action.shut_down", "关机"),
+    ("action.log_out", "退出登录"),
+    ("action.empty_trash", "清倒废纸篓"),
+    ("action.toggle_dark_mode", "切换深色模式"),
+    ("action.eject_all_disks", "推出所有磁盘"),
+    ("action.show_desktop", "显示桌面"),
+    ("diagnostics.spotlight", "聚焦搜索"),
+    ("diagnostics.swift", "Swift（应用图标）"),
+    ("diagnostics.config_dir", "配置文件夹"),
+    ("diagnostics.config_file", "配置文件"),
+    ("diagnostics.shortcut", "全局快捷键"),
+    ("diagnostics.wallpaper", "壁纸"),
+    ("diagnostics.icon_cache", "图标缓存"),
+    ("resource.app", "应用"),
+    ("resource.file", "文件"),
+    ("resource.folder", "文件夹"),
+    ("resource.category", "分类"),
+    ("resource.profile", "配置方案"),
+    ("resource.backup", "备份"),
+    ("resource.plugin", "插件"),
+    ("resource.wallpaper", "壁纸"),
+    ("io.read", "读取"),
+    ("io.write", "写入"),
+    ("io.copy", "复制"),
+    ("io.delete", "删除"),
+    ("field.not_an_object", "设置必须是 JSON 对象"),
+    ("field.read_only", "为只读项"),
+    ("field.unknown_field", "未知的设置项"),
+    ("field.invalid_value", "值无效：{detail}"),
+    ("field.between", "必须介于 {min} 和 {max} 之间"),
+    ("field.one_of", "必须是 {values} 之一"),
+    ("field.unknown_theme", "未知的主题“{name}”"),
+    ("field.empty", "不能为空"),
+    ("field.at_least", "不能小于 {min}"),
+    ("field.unknown_category", "未知的分类“{name}”"),
+    ("field.invalid_category", "“{name}”不是有效的分类名称"),
+    ("field.duplicate", "“{name}”重复出现"),
+    ("error.not_found", "找不到{resource}“{name}”"),
+    ("error.already_exists", "已存在名为“{name}”的{resource}"),
+    ("error.invalid_name", "“{name}”不是有效的{resource}名称"),
+    ("error.built_in_category", "“{name}”是内置分类"),
+    ("error.category_cycle", "无法将“{from}”移动到“{to}”"),
+    ("error.active_profile", "无法删除当前使用的配置方案“{name}”"),
+    ("error.invalid_config", "{source} 不是有效的配置：{detail}"),
+    ("error.invalid_archive", "无效的配置方案归档：{detail}"),
+    ("error.invalid_pattern", "无效的匹配规则“{pattern}”：{detail}"),
+    ("error.invalid_date", "无效的日期“{value}”"),
+    ("error.invalid_range", "起始日期晚于结束日期（{from} 至 {to}）"),
+    ("error.invalid_log_level", "未知的日志级别“{value}”"),
+    ("error.unsupported_format", "不支持的图片格式（支持 PNG/JPG/JPEG/WebP/GIF）"),
+    ("error.not_managed", "只能删除应用保存的壁纸"),
+    ("error.invalid_fields", "设置无效：{fields}"),
+    ("error.stale_config", "配置已被修改（版本 {revision} 不是 {current}），请重新加载后再试"),
+    ("error.nothing_to_undo", "没有可撤销的操作"),
+    ("error.nothing_to_redo", "没有可重做的操作"),
+    ("error.history_conflict", "无法撤销或重做“{label}”：{field} 之后又被修改，该操作已从历史记录中移除"),
+    ("error.confirmation_required", "“{action}”需要确认"),
+    ("error.command_failed", "{program} 执行失败：{detail}"),
+    ("error.io", "{action} {path} 失败：{detail}"),
+    ("error.plugin", "插件错误：{detail}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn keys(catalog: &[(&'static str, &str)]) -> BTreeSet<&'static str> {
+        catalog.iter().map(|(key, _)| *key).collect()
+    }
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{').skip(1).filter_map(|rest| rest.split_once('}')).map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys_and_placeholders() {
+        for locale in [Locale::En, Locale::ZhCn] {
+            assert_eq!(keys(locale.catalog()).len(), locale.catalog().len(), "duplicate key in {:?}", locale);
+        }
+        let missing: Vec<_> = keys(EN).symmetric_difference(&keys(ZH_CN)).copied().collect();
+        assert!(missing.is_empty(), "keys missing from a catalog: {:?}", missing);
+        for (key, text) in EN {
+            assert_eq!(placeholders(text), placeholders(tr(Locale::ZhCn, key)), "{}", key);
+        }
+    }
+
+    #[test]
+    fn resolves_settings_and_system_tags() {
+        assert_eq!(resolve("zh-CN", Some("en-US")), Locale::ZhCn);
+        assert_eq!(resolve("en", Some("zh-Hans-CN")), Locale::En);
+        assert_eq!(resolve("system", Some("zh-Hans-CN")), Locale::ZhCn);
+        assert_eq!(resolve("system", Some("fr-FR")), Locale::En);
+        assert_eq!(resolve("system", None), Locale::En);
+        assert_eq!(tr(Locale::ZhCn, "tray.quit"), "退出");
+    }
+}
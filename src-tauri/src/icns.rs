@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{self, BundleInfo};
+
+const ICNS_MAGIC: &[u8; 4] = b"icns";
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+const HEADER_LEN: usize = 8;
+
+/// Element types that may hold PNG data, with their size in pixels. Other
+/// types are legacy bitmaps or metadata; JPEG 2000 payloads of these types
+/// are skipped when the data is checked.
+const PNG_TYPES: [(&[u8; 4], u32); 11] = [
+    (b"icp4", 16),
+    (b"icp5", 32),
+    (b"ic11", 32),
+    (b"icp6", 64),
+    (b"ic12", 64),
+    (b"ic07", 128),
+    (b"ic08", 256),
+    (b"ic13", 256),
+    (b"ic09", 512),
+    (b"ic14", 512),
+    (b"ic10", 1024),
+];
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// `(type, data)` for each complete element. Parsing stops at the first
+/// element that runs past the end of `data`.
+fn elements(data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    if data.get(..4) != Some(ICNS_MAGIC.as_slice()) {
+        return vec![];
+    }
+    let end = be_u32(data, 4).map_or(data.len(), |len| (len as usize).min(data.len()));
+    let mut elements = Vec::new();
+    let mut at = HEADER_LEN;
+    while at + HEADER_LEN <= end {
+        let Some(len) = be_u32(data, at + 4).map(|len| len as usize) else {
+            break;
+        };
+        if len < HEADER_LEN || at + len > end {
+            break;
+        }
+        elements.push((&data[at..at + 4], &data[at + HEADER_LEN..at + len]));
+        at += len;
+    }
+    elements
+}
+
+/// Width of a PNG from its IHDR chunk.
+fn png_width(png: &[u8]) -> Option<u32> {
+    if !png.starts_with(PNG_SIGNATURE) || png.get(12..16) != Some(b"IHDR".as_slice()) {
+        return None;
+    }
+    be_u32(png, 16)
+}
+
+/// The PNG closest to `size` pixels: the smallest at least that large,
+/// otherwise the largest there is.
+pub(crate) fn best_png(data: &[u8], size: u32) -> Option<&[u8]> {
+    let mut pngs: Vec<(u32, &[u8])> = elements(data)
+        .into_iter()
+        .filter(|(kind, _)| PNG_TYPES.iter().any(|(t, _)| t.as_slice() == *kind))
+        .filter_map(|(_, png)| png_width(png).map(|width| (width, png)))
+        .collect();
+    pngs.sort_by_key(|(width, _)| *width);
+    pngs.iter()
+        .find(|(width, _)| *width >= size)
+        .or(pngs.last())
+        .map(|(_, png)| *png)
+}
+
+/// The bundle's `.icns` file, from `CFBundleIconFile` (the extension is
+/// optional there).
+fn icon_path(app_path: &Path, info: &BundleInfo) -> Option<PathBuf> {
+    let name = info.icon_file.as_deref().filter(|name| !name.is_empty())?;
+    let mut path = app_path.join("Contents/Resources").join(name);
+    if path.extension().is_none() {
+        path.set_extension("icns");
+    }
+    Some(path)
+}
+
+/// The app's icon as PNG data, if its bundle ships an `.icns` with a PNG
+/// representation. Apps whose icon only lives in an asset catalog return
+/// `None`.
+pub(crate) fn app_icon_png(app_path: &Path, size: u32) -> Option<Vec<u8>> {
+    let path = icon_path(app_path, &bundle::read_info(app_path))?;
+    let data = fs::read(path).ok()?;
+    best_png(&data, size).map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/icns").join(name);
+        fs::read(path).unwrap()
+    }
+
+    fn width(png: Option<&[u8]>) -> Option<u32> {
+        png.and_then(png_width)
+    }
+
+    #[test]
+    fn picks_the_closest_png_representation() {
+        let data = fixture("png_sizes.icns");
+        let kinds: Vec<_> = elements(&data).into_iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"TOC ", b"icp4", b"ic07", b"ic09"]);
+
+        assert_eq!(width(best_png(&data, 16)), Some(16));
+        assert_eq!(width(best_png(&data, 64)), Some(128));
+        assert_eq!(width(best_png(&data, 128)), Some(128));
+        assert_eq!(width(best_png(&data, 300)), Some(512));
+        assert_eq!(width(best_png(&data, 1024)), Some(512));
+        assert!(best_png(&data, 128).unwrap().starts_with(PNG_SIGNATURE));
+    }
+
+    #[test]
+    fn skips_jpeg2000_and_damaged_files() {
+        assert_eq!(best_png(&fixture("jpeg2000_only.icns"), 128), None);
+        // The 256px element is cut off; the complete 32px one is still used.
+        assert_eq!(width(best_png(&fixture("truncated.icns"), 128)), Some(32));
+        assert_eq!(best_png(b"not an icns file", 128), None);
+        assert_eq!(best_png(b"icns\x00\x00\x00\x10ic07\x00\x00\x00\x02", 128), None);
+    }
+
+    #[test]
+    fn reads_the_icon_named_in_info_plist() {
+        let app = std::env::temp_dir().join(format!("macappcontrol_test_icns_{}.app", std::process::id()));
+        fs::create_dir_all(app.join("Contents/Resources")).unwrap();
+        fs::write(
+            app.join("Contents/Info.plist"),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict>
+  <key>CFBundleIconFile</key><string>AppIcon</string>
+</dict></plist>"#,
+        )
+        .unwrap();
+        assert_eq!(app_icon_png(&app, 128), None);
+
+        fs::write(app.join("Contents/Resources/AppIcon.icns"), fixture("png_sizes.icns")).unwrap();
+        assert_eq!(app_icon_png(&app, 128).as_deref().and_then(png_width), Some(128));
+        let _ = fs::remove_dir_all(&app);
+    }
+}
@@ -1,11 +1,14 @@
 use base64::{engine::general_purpose, Engine as _};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::config::get_config_path;
 
-fn get_cache_dir() -> PathBuf {
+/// Icons are cached at this size in pixels, or the nearest one available.
+const ICON_SIZE: u32 = 128;
+
+pub(crate) fn get_cache_dir() -> PathBuf {
     let mut path = get_config_path();
     path.push("icons");
     let _ = fs::create_dir_all(&path);
@@ -17,6 +20,10 @@ pub fn get_app_icon(path: String) -> Option<String> {
     get_app_icon_cached(&path)
 }
 
+fn png_data_url(data: &[u8]) -> String {
+    format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(data))
+}
+
 fn get_app_icon_cached(app_path: &str) -> Option<String> {
     let cache_dir = get_cache_dir();
     let hashed_name = format!("{:x}", md5::compute(app_path));
@@ -24,17 +31,24 @@ fn get_app_icon_cached(app_path: &str) -> Option<String> {
 
     if cache_path.exists() {
         if let Ok(data) = fs::read(&cache_path) {
-            return Some(format!(
-                "data:image/png;base64,{}",
-                general_purpose::STANDARD.encode(data)
-            ));
+            return Some(png_data_url(&data));
+        }
+    }
+
+    // Reading the bundle's .icns directly is much faster than starting
+    // swift; swift remains for icons that only live in an asset catalog.
+    if let Some(png) = crate::icns::app_icon_png(Path::new(app_path), ICON_SIZE) {
+        if let Err(e) = fs::write(&cache_path, &png) {
+            tracing::warn!(app = app_path, "could not cache icon: {}", e);
         }
+        return Some(png_data_url(&png));
     }
+    tracing::debug!(app = app_path, "no PNG in the bundle's icns, falling back to swift");
 
     let swift_code = format!(
         "import AppKit; \
          let img = NSWorkspace.shared.icon(forFile: \"{}\"); \
-         img.size = NSSize(width: 128, height: 128); \
+         img.size = NSSize(width: {ICON_SIZE}, height: {ICON_SIZE}); \
          guard let tiff = img.tiffRepresentation, \
                let bitmap = NSBitmapImageRep(data: tiff), \
                let data = bitmap.representation(using: .png, properties: [:]) \
@@ -46,15 +60,15 @@ fn get_app_icon_cached(app_path: &str) -> Option<String> {
 
     let status = Command::new("swift").arg("-e").arg(swift_code).status();
 
-    if let Ok(s) = status {
-        if s.success() {
+    match status {
+        Ok(s) if s.success() => {
             if let Ok(data) = fs::read(&cache_path) {
-                return Some(format!(
-                    "data:image/png;base64,{}",
-                    general_purpose::STANDARD.encode(data)
-                ));
+                return Some(png_data_url(&data));
             }
+            tracing::warn!(app = app_path, "swift exited cleanly but wrote no icon");
         }
+        Ok(s) => tracing::warn!(app = app_path, status = %s, "icon extraction failed"),
+        Err(e) => tracing::warn!(app = app_path, "could not run swift: {}", e),
     }
     None
 }
@@ -1,36 +1,76 @@
 mod apps;
+mod archive;
+mod backups;
+mod bundle;
+mod categories;
 mod config;
+mod config_patch;
+mod config_watcher;
+mod diagnostics;
+mod error;
+mod files;
+mod history;
+mod i18n;
+mod icns;
 mod icons;
+mod logging;
+mod migrations;
+mod plugins;
+mod profiles;
 mod scripts;
 mod shortcuts;
+mod smart_categories;
+mod sync;
+mod system_actions;
+#[cfg(test)]
+mod test_support;
+mod usage;
 mod wallpaper;
 
 pub use apps::{get_installed_apps, launch_app, reveal_in_finder, AppInfo};
+pub use archive::{export_profile, import_profile, ImportMode};
+pub use backups::{list_config_backups, restore_config_backup, ConfigBackup};
+pub use categories::{add_app_tag, merge_categories, remove_app_tag, rename_category};
 pub use config::{
-    add_category, get_config, remove_category, save_config_command, update_app_category, AppConfig, ScriptAction,
+    add_category, get_config, remove_category, save_config_command, take_config_load_error, update_app_category,
+    AppConfig, ConfigLoadError, ScriptAction,
 };
+pub use config_patch::{patch_config, FieldError};
+pub use diagnostics::{export_support_bundle, run_diagnostics, Check, CheckStatus, DiagnosticsReport};
+pub use files::{copy_path, open_file, search_files, update_file_search_settings, FileResult};
+pub use history::{get_history_state, redo, undo, HistoryState};
 pub use icons::get_app_icon;
+pub use logging::{get_recent_logs, LogEntry};
+pub use plugins::{activate_plugin_result, list_plugins, search_plugins, PluginManifest, PluginResult};
 pub use scripts::{add_script, remove_script, run_script, update_script};
+pub use profiles::{create_profile, delete_profile, list_profiles, switch_profile, ProfileInfo};
 pub use shortcuts::update_shortcut;
+pub use smart_categories::{get_smart_categories, set_smart_categories, Rule, SmartCategory};
+pub use sync::{set_sync_folder, sync_config, Resolve, SyncConflict, SyncReport, SyncStatus};
+pub use system_actions::{list_system_actions, run_system_action, SystemAction, SystemActionInfo};
+pub use usage::{
+    clear_usage_data, export_usage_stats, get_usage_stats, ExportFormat, GroupBy, UsageRange, UsageScope, UsageStats,
+};
 pub use wallpaper::{delete_wallpaper, get_wallpapers_dir, import_wallpaper, list_wallpapers, WallpaperFile};
 
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
-    AppHandle, Manager,
+    AppHandle, Emitter, Manager,
 };
 
 const TRAY_ID: &str = "main";
 
 pub fn update_tray_menu(app: &AppHandle) {
     let config = config::load_config();
+    let locale = i18n::current();
     let tray = match app.tray_by_id(TRAY_ID) {
         Some(t) => t,
         None => return,
     };
 
     let menu = Menu::new(app).unwrap();
-    let _ = MenuItem::with_id(app, "show", "Show app", true, None::<&str>).map(|i| menu.append(&i));
+    let _ = MenuItem::with_id(app, "show", i18n::tr(locale, "tray.show"), true, None::<&str>).map(|i| menu.append(&i));
     let _ = PredefinedMenuItem::separator(app).map(|i| menu.append(&i));
 
     if !config.scripts.is_empty() {
@@ -41,13 +81,48 @@ pub fn update_tray_menu(app: &AppHandle) {
         let _ = PredefinedMenuItem::separator(app).map(|i| menu.append(&i));
     }
 
-    let _ = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>).map(|i| menu.append(&i));
+    let profiles = profiles::list();
+    if profiles.len() > 1 {
+        if let Ok(submenu) = Submenu::with_id(app, "profiles", i18n::tr(locale, "tray.profiles"), true) {
+            for profile in &profiles {
+                let id = format!("profile:{}", profile.name);
+                let _ = CheckMenuItem::with_id(app, id, &profile.name, true, profile.active, None::<&str>)
+                    .map(|i| submenu.append(&i));
+            }
+            let _ = menu.append(&submenu);
+            let _ = PredefinedMenuItem::separator(app).map(|i| menu.append(&i));
+        }
+    }
+
+    let _ = CheckMenuItem::with_id(
+        app,
+        "incognito",
+        i18n::tr(locale, "tray.incognito"),
+        true,
+        config.incognito,
+        None::<&str>,
+    )
+        .map(|i| menu.append(&i));
+    let _ = MenuItem::with_id(app, "undo", i18n::tr(locale, "tray.undo"), true, None::<&str>).map(|i| menu.append(&i));
+    let _ = MenuItem::with_id(app, "redo", i18n::tr(locale, "tray.redo"), true, None::<&str>).map(|i| menu.append(&i));
+    let _ = PredefinedMenuItem::separator(app).map(|i| menu.append(&i));
+    let _ = MenuItem::with_id(app, "quit", i18n::tr(locale, "tray.quit"), true, None::<&str>).map(|i| menu.append(&i));
 
     let _ = tray.set_menu(Some(menu));
 }
 
+/// Pushes a config that replaced the cached one out to the running app:
+/// global shortcut, tray menu and the frontend.
+pub(crate) fn apply_config(app: &AppHandle, config: &AppConfig) {
+    shortcuts::register_app_shortcut(app, &config.shortcut);
+    logging::set_level(&config.log_level);
+    update_tray_menu(app);
+    let _ = app.emit("config-changed", config);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init(&config::load_config().log_level);
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -66,8 +141,24 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
-            let config = config::load_config();
+            let mut config = config::load_config();
+            if let Some(error) = config::pending_load_error() {
+                tracing::warn!(backup = ?error.backup_path, "config could not be parsed: {}", error.message);
+                let _ = app.emit("config-load-error", error);
+            }
+            match sync::sync_active(None) {
+                Ok(Some((synced, report))) => {
+                    tracing::info!(status = ?report.status, "synced config at startup");
+                    config = synced;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("startup sync failed: {}", e),
+            }
             shortcuts::register_app_shortcut(app.handle(), &config.shortcut);
+            files::start_file_index();
+            if let Err(e) = usage::enforce_retention() {
+                tracing::warn!("could not apply usage retention: {}", e);
+            }
 
             let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
@@ -84,13 +175,33 @@ pub fn run() {
                         let script_name = &id["script:".len()..];
                         let config = config::load_config();
                         if let Some(script) = config.scripts.iter().find(|s| s.name == script_name) {
-                            scripts::run_script(script.command.clone(), script.cwd.clone());
+                            if let Err(e) = scripts::run_script(script.command.clone(), script.cwd.clone()) {
+                                tracing::error!(script = script_name, "tray script failed: {}", e);
+                            }
+                        }
+                    } else if id == "incognito" {
+                        if config::update_config(|c| c.incognito = !c.incognito).is_ok() {
+                            apply_config(app, &config::load_config());
+                        }
+                    } else if id == "undo" || id == "redo" {
+                        let step = if id == "undo" {
+                            history::undo_change()
+                        } else {
+                            history::redo_change()
+                        };
+                        if let Ok((_, config)) = step {
+                            apply_config(app, &config);
+                        }
+                    } else if let Some(name) = id.strip_prefix("profile:") {
+                        if let Ok(config) = profiles::switch(name) {
+                            apply_config(app, &config);
                         }
                     }
                 })
                 .build(app)?;
 
             update_tray_menu(app.handle());
+            config_watcher::watch_config(app.handle());
 
             Ok(())
         })
@@ -101,6 +212,30 @@ pub fn run() {
             update_app_category,
             get_config,
             save_config_command,
+            take_config_load_error,
+            patch_config,
+            rename_category,
+            merge_categories,
+            add_app_tag,
+            remove_app_tag,
+            undo,
+            redo,
+            get_history_state,
+            get_smart_categories,
+            set_smart_categories,
+            get_usage_stats,
+            export_usage_stats,
+            clear_usage_data,
+            list_config_backups,
+            restore_config_backup,
+            export_profile,
+            import_profile,
+            list_profiles,
+            create_profile,
+            switch_profile,
+            delete_profile,
+            set_sync_folder,
+            sync_config,
             add_category,
             remove_category,
             update_shortcut,
@@ -112,7 +247,19 @@ pub fn run() {
             get_wallpapers_dir,
             list_wallpapers,
             import_wallpaper,
-            delete_wallpaper
+            delete_wallpaper,
+            list_plugins,
+            search_plugins,
+            activate_plugin_result,
+            search_files,
+            update_file_search_settings,
+            open_file,
+            copy_path,
+            list_system_actions,
+            run_system_action,
+            get_recent_logs,
+            run_diagnostics,
+            export_support_bundle
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,204 @@
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::level_filters::LevelFilter;
+use tracing::Level;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::config::get_config_path;
+use crate::error::AppError;
+
+/// Values accepted for `AppConfig::log_level`, most severe first.
+pub(crate) const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+const LOG_PREFIX: &str = "app";
+const LOG_SUFFIX: &str = "log";
+/// One file per day; older ones are deleted.
+const MAX_LOG_FILES: usize = 7;
+const DEFAULT_LIMIT: usize = 200;
+
+static LEVEL_HANDLE: OnceCell<reload::Handle<LevelFilter, Registry>> = OnceCell::new();
+
+pub(crate) fn logs_dir() -> PathBuf {
+    let mut dir = get_config_path();
+    dir.push("logs");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+fn level_filter(level: &str) -> LevelFilter {
+    level.parse().unwrap_or(LevelFilter::INFO)
+}
+
+/// Installs the global subscriber: JSON lines in daily files under
+/// `logs_dir()`, plus plain text on stderr.
+pub(crate) fn init(level: &str) {
+    let (filter, handle) = reload::Layer::new(level_filter(level));
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(logs_dir());
+    let file = appender.ok().map(|appender| fmt::layer().json().with_writer(appender));
+    let stderr = fmt::layer().with_writer(std::io::stderr);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(file)
+        .with(stderr)
+        .try_init()
+        .is_ok()
+    {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+}
+
+/// Applies a changed `log_level` to the running subscriber.
+pub(crate) fn set_level(level: &str) {
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        let _ = handle.reload(level_filter(level));
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields recorded with the event besides the message.
+    pub fields: Map<String, Value>,
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let Ok(Value::Object(mut event)) = serde_json::from_str(line) else {
+        return None;
+    };
+    let mut fields = match event.remove("fields") {
+        Some(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let message = match fields.remove("message") {
+        Some(Value::String(message)) => message,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    let text = |key: &str| event.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+    Some(LogEntry {
+        timestamp: text("timestamp"),
+        level: text("level").to_ascii_lowercase(),
+        target: text("target"),
+        message,
+        fields,
+    })
+}
+
+/// The newest `limit` entries in `dir` at `min_level` or more severe,
+/// newest first.
+fn recent(dir: &Path, min_level: LevelFilter, limit: usize) -> Vec<LogEntry> {
+    if limit == 0 {
+        return vec![];
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX))
+        })
+        .collect();
+    // Names carry the date, so they sort oldest first.
+    files.sort();
+
+    let mut entries = Vec::new();
+    for file in files.iter().rev() {
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+        for entry in content.lines().rev().filter_map(parse_line) {
+            if entry.level.parse::<Level>().is_ok_and(|level| level <= min_level) {
+                entries.push(entry);
+                if entries.len() == limit {
+                    return entries;
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Log entries for the settings page; `level` defaults to everything.
+#[tauri::command]
+pub fn get_recent_logs(level: Option<String>, limit: Option<usize>) -> Result<Vec<LogEntry>, AppError> {
+    let level = level.unwrap_or_else(|| "trace".to_string());
+    if !LOG_LEVELS.contains(&level.as_str()) {
+        return Err(AppError::InvalidLogLevel { value: level });
+    }
+    Ok(recent(&logs_dir(), level_filter(&level), limit.unwrap_or(DEFAULT_LIMIT)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parses_events_as_written() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(fmt::layer().json().with_writer(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(path = "/Applications/Xcode.app", "launch failed");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let entry = parse_line(output.trim()).expect("a JSON event");
+        assert_eq!(entry.level, "warn");
+        assert_eq!(entry.message, "launch failed");
+        assert_eq!(entry.fields["path"], "/Applications/Xcode.app");
+        assert!(entry.target.ends_with("logging::tests"));
+        assert!(parse_line("not json").is_none());
+    }
+
+    #[test]
+    fn reads_newest_entries_at_or_above_a_level() {
+        let dir = crate::test_support::temp_dir("logs");
+        let line = |level: &str, message: &str| {
+            format!(
+                "{{\"timestamp\":\"t\",\"level\":\"{}\",\"fields\":{{\"message\":\"{}\"}},\"target\":\"app\"}}\n",
+                level, message
+            )
+        };
+        fs::write(dir.join("app.2026-03-01.log"), line("ERROR", "old error") + &line("INFO", "old info")).unwrap();
+        fs::write(dir.join("app.2026-03-02.log"), line("WARN", "warning") + &line("DEBUG", "detail")).unwrap();
+        fs::write(dir.join("other.txt"), line("ERROR", "ignored")).unwrap();
+
+        let messages = |level, limit| -> Vec<String> {
+            recent(&dir, level, limit).into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(messages(LevelFilter::TRACE, 3), ["detail", "warning", "old info"]);
+        assert_eq!(messages(LevelFilter::WARN, 10), ["warning", "old error"]);
+        assert_eq!(messages(LevelFilter::ERROR, 10), ["old error"]);
+        assert!(messages(LevelFilter::TRACE, 0).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use serde_json::Value;
+
+/// Schema version written by this build. Bump it together with a new entry
+/// in `MIGRATIONS`.
+pub(crate) const CURRENT_SCHEMA_VERSION: u32 = 3;
+
+type Migration = fn(&mut Value);
+
+/// `MIGRATIONS[n]` upgrades a config from schema version `n` to `n + 1`.
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] =
+    [v0_normalize_category_order, v1_category_order_tree, v2_tags_from_categories];
+
+pub(crate) fn schema_version(value: &Value) -> u32 {
+    value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Runs every migration between the file's `schema_version` and the current
+/// one, in order. Files from a newer build are left untouched.
+pub(crate) fn migrate(mut value: Value) -> Value {
+    if !value.is_object() {
+        return value;
+    }
+    let from = schema_version(&value);
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        migration(&mut value);
+        value["schema_version"] = Value::from(version as u32 + 1);
+    }
+    value
+}
+
+/// The built-in sidebar order as of schema version 1. Later changes to the
+/// defaults must not change what this migration produces.
+const V1_DEFAULT_ORDER: [&str; 8] = [
+    "Frequent",
+    "Scripts",
+    "Development",
+    "Social",
+    "Design",
+    "Productivity",
+    "User Apps",
+    "System",
+];
+
+fn string_array(value: &Value, key: &str) -> Vec<String> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// v0 -> v1: drops the legacy "All" entry from `category_order` and fills an
+/// empty order with the built-in categories followed by the user's own.
+fn v0_normalize_category_order(value: &mut Value) {
+    let mut order = string_array(value, "category_order");
+    order.retain(|c| c != "All");
+    if order.is_empty() {
+        order = V1_DEFAULT_ORDER.map(str::to_string).to_vec();
+        for cat in string_array(value, "user_categories") {
+            if !order.contains(&cat) {
+                order.push(cat);
+            }
+        }
+    }
+    value["category_order"] = Value::from(order);
+}
+
+/// v1 -> v2: `category_order` becomes a tree of `{ name, children }` nodes.
+/// Entries containing `/` are nested under their parents.
+fn v1_category_order_tree(value: &mut Value) {
+    let mut tree: Vec<Value> = vec![];
+    for path in string_array(value, "category_order") {
+        let mut siblings = &mut tree;
+        for name in path.split('/').map(str::trim).filter(|s| !s.is_empty()) {
+            let index = match siblings.iter().position(|n| n["name"] == name) {
+                Some(index) => index,
+                None => {
+                    siblings.push(serde_json::json!({ "name": name, "children": [] }));
+                    siblings.len() - 1
+                }
+            };
+            siblings = siblings[index]["children"]
+                .as_array_mut()
+                .expect("nodes are created with a children array");
+        }
+    }
+    fn drop_empty_children(nodes: &mut [Value]) {
+        for node in nodes {
+            let children = node["children"].as_array_mut().expect("children array");
+            drop_empty_children(children);
+            if children.is_empty() {
+                node.as_object_mut().expect("node object").remove("children");
+            }
+        }
+    }
+    drop_empty_children(&mut tree);
+    value["category_order"] = Value::from(tree);
+}
+
+/// v2 -> v3: every app's single category becomes its first tag.
+fn v2_tags_from_categories(value: &mut Value) {
+    let mut tags = value
+        .get("tags")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    if let Some(categories) = value.get("categories").and_then(Value::as_object) {
+        for (path, category) in categories {
+            tags.entry(path.clone())
+                .or_insert_with(|| Value::from(vec![category.clone()]));
+        }
+    }
+    value["tags"] = Value::Object(tags);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn fixture(name: &str) -> Value {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/config")
+            .join(name);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Runs migrations up to `target` only, to check a single step.
+    fn migrate_to(mut value: Value, target: u32) -> Value {
+        for (version, migration) in MIGRATIONS.iter().enumerate().take(target as usize) {
+            migration(&mut value);
+            value["schema_version"] = Value::from(version as u32 + 1);
+        }
+        value
+    }
+
+    #[test]
+    fn v0_to_v1_removes_all_and_keeps_order() {
+        let migrated = migrate_to(fixture("v0_with_all.json"), 1);
+        assert_eq!(migrated, fixture("v1_with_all.expected.json"));
+    }
+
+    #[test]
+    fn v0_to_v1_fills_empty_order() {
+        let migrated = migrate_to(fixture("v0_empty_order.json"), 1);
+        assert_eq!(migrated, fixture("v1_empty_order.expected.json"));
+    }
+
+    #[test]
+    fn v1_to_v2_builds_category_tree() {
+        let migrated = migrate_to(fixture("v1_nested_order.json"), 2);
+        assert_eq!(migrated, fixture("v2_nested_order.expected.json"));
+        assert_eq!(
+            migrate_to(fixture("v0_with_all.json"), 2),
+            fixture("v2_with_all.expected.json")
+        );
+    }
+
+    #[test]
+    fn v2_to_v3_tags_apps_with_their_category() {
+        let migrated = migrate(fixture("v0_with_all.json"));
+        assert_eq!(migrated, fixture("v3_with_all.expected.json"));
+    }
+
+    #[test]
+    fn migrated_fixtures_deserialize() {
+        for name in ["v0_with_all.json", "v0_empty_order.json", "v1_nested_order.json"] {
+            let config: AppConfig = serde_json::from_value(migrate(fixture(name))).unwrap();
+            assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+        }
+    }
+
+    #[test]
+    fn current_and_newer_versions_are_untouched() {
+        let current = fixture("v3_with_all.expected.json");
+        assert_eq!(migrate(current.clone()), current);
+
+        let mut newer = current;
+        newer["schema_version"] = Value::from(CURRENT_SCHEMA_VERSION + 1);
+        newer["category_order"] = Value::from(vec!["All"]);
+        assert_eq!(migrate(newer.clone()), newer);
+    }
+}
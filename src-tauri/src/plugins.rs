@@ -0,0 +1,719 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+use wasmtime::{Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+use crate::config::{expand_home, get_config_path};
+use crate::error::{AppError, Resource};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const STORAGE_FILE: &str = "storage.json";
+const MAX_HOST_INPUT: usize = 64 * 1024;
+const MAX_RESULTS: usize = 50;
+
+// Return codes for host functions. Non-negative values are byte counts.
+const ERR_DENIED: i32 = -1;
+const ERR_INVALID: i32 = -2;
+const ERR_NOT_FOUND: i32 = -3;
+
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).expect("failed to create wasm engine")
+});
+
+/// Host functions only depend on the engine, so one linker serves every call.
+static LINKER: Lazy<Result<Linker<HostState>, String>> = Lazy::new(|| build_linker(&ENGINE));
+
+/// Compiled modules by entry path, with the file's mtime when compiled.
+/// Compiling takes far longer than a search, and searches run per keystroke.
+static MODULES: Lazy<Mutex<HashMap<PathBuf, (SystemTime, Module)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginPermission {
+    Search,
+    OpenUrl,
+    Storage,
+    Filesystem,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PluginLimits {
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+    #[serde(default = "default_memory_bytes")]
+    pub memory_bytes: usize,
+}
+
+fn default_fuel() -> u64 {
+    10_000_000
+}
+
+fn default_memory_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self {
+            fuel: default_fuel(),
+            memory_bytes: default_memory_bytes(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default = "default_entry")]
+    pub entry: String,
+    #[serde(default)]
+    pub permissions: Vec<PluginPermission>,
+    /// Directories readable through `read_file`, only honoured with the `filesystem` permission.
+    #[serde(default)]
+    pub filesystem: Vec<String>,
+    #[serde(default)]
+    pub limits: PluginLimits,
+}
+
+fn default_entry() -> String {
+    "plugin.wasm".to_string()
+}
+
+impl PluginManifest {
+    fn allows(&self, permission: PluginPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PluginResult {
+    #[serde(default)]
+    pub plugin_id: String,
+    #[serde(default)]
+    pub id: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Everything a single plugin call produced. URLs are collected rather than
+/// opened so callers decide when (and whether) to hand them to the system.
+#[derive(Default, Debug)]
+pub struct PluginOutput {
+    pub results: Vec<PluginResult>,
+    pub opened_urls: Vec<String>,
+    pub denied: Vec<PluginPermission>,
+}
+
+struct HostState {
+    manifest: PluginManifest,
+    dir: PathBuf,
+    storage: HashMap<String, String>,
+    storage_dirty: bool,
+    output: PluginOutput,
+    limits: StoreLimits,
+}
+
+impl HostState {
+    fn check(&mut self, permission: PluginPermission) -> bool {
+        if self.manifest.allows(permission) {
+            return true;
+        }
+        if !self.output.denied.contains(&permission) {
+            self.output.denied.push(permission);
+        }
+        false
+    }
+}
+
+pub struct WasmPlugin {
+    pub manifest: PluginManifest,
+    dir: PathBuf,
+    module: Module,
+}
+
+fn plugins_dir() -> PathBuf {
+    let mut dir = get_config_path();
+    dir.push("plugins");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+fn is_valid_plugin_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !id.starts_with('.')
+}
+
+fn read_manifest(dir: &Path) -> Result<PluginManifest, String> {
+    let content = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to read plugin manifest: {}", e))?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid plugin manifest: {}", e))?;
+    if !is_valid_plugin_id(&manifest.id) {
+        return Err(format!("Invalid plugin id: {}", manifest.id));
+    }
+    if manifest.entry.contains('/') || manifest.entry.contains("..") {
+        return Err("Plugin entry must be a file inside the plugin directory".to_string());
+    }
+    Ok(manifest)
+}
+
+fn load_storage(dir: &Path) -> HashMap<String, String> {
+    fs::read_to_string(dir.join(STORAGE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn read_guest(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<Vec<u8>> {
+    let (ptr, len) = (usize::try_from(ptr).ok()?, usize::try_from(len).ok()?);
+    if len > MAX_HOST_INPUT {
+        return None;
+    }
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    memory.data(&caller).get(ptr..ptr.checked_add(len)?).map(|s| s.to_vec())
+}
+
+fn read_guest_str(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    read_guest(caller, ptr, len).and_then(|b| String::from_utf8(b).ok())
+}
+
+/// Copies as much of `data` as fits into the guest buffer and returns the
+/// full length, so a guest can retry with a larger buffer.
+fn write_guest(caller: &mut Caller<'_, HostState>, ptr: i32, cap: i32, data: &[u8]) -> i32 {
+    let (Ok(ptr), Ok(cap)) = (usize::try_from(ptr), usize::try_from(cap)) else {
+        return ERR_INVALID;
+    };
+    let Some(memory) = caller.get_export("memory").and_then(Extern::into_memory) else {
+        return ERR_INVALID;
+    };
+    let n = data.len().min(cap);
+    if memory.write(&mut *caller, ptr, &data[..n]).is_err() {
+        return ERR_INVALID;
+    }
+    i32::try_from(data.len()).unwrap_or(ERR_INVALID)
+}
+
+fn path_is_granted(manifest: &PluginManifest, target: &Path) -> bool {
+    let Ok(target) = target.canonicalize() else {
+        return false;
+    };
+    manifest
+        .filesystem
+        .iter()
+        .filter_map(|root| expand_home(root).canonicalize().ok())
+        .any(|root| target.starts_with(root))
+}
+
+fn build_linker(engine: &Engine) -> Result<Linker<HostState>, String> {
+    let mut linker = Linker::new(engine);
+    let map_err = |e: wasmtime::Error| format!("Failed to define host function: {}", e);
+
+    linker
+        .func_wrap(
+            "host",
+            "emit_result",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+                if !caller.data_mut().check(PluginPermission::Search) {
+                    return ERR_DENIED;
+                }
+                let Some(bytes) = read_guest(&mut caller, ptr, len) else {
+                    return ERR_INVALID;
+                };
+                let Ok(mut result) = serde_json::from_slice::<PluginResult>(&bytes) else {
+                    return ERR_INVALID;
+                };
+                let state = caller.data_mut();
+                if state.output.results.len() >= MAX_RESULTS {
+                    return ERR_INVALID;
+                }
+                result.plugin_id = state.manifest.id.clone();
+                state.output.results.push(result);
+                0
+            },
+        )
+        .map_err(map_err)?;
+
+    linker
+        .func_wrap(
+            "host",
+            "open_url",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+                if !caller.data_mut().check(PluginPermission::OpenUrl) {
+                    return ERR_DENIED;
+                }
+                let Some(url) = read_guest_str(&mut caller, ptr, len) else {
+                    return ERR_INVALID;
+                };
+                if !(url.starts_with("https://") || url.starts_with("http://")) {
+                    return ERR_INVALID;
+                }
+                caller.data_mut().output.opened_urls.push(url);
+                0
+            },
+        )
+        .map_err(map_err)?;
+
+    linker
+        .func_wrap(
+            "host",
+            "storage_get",
+            |mut caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32, out_ptr: i32, out_cap: i32| -> i32 {
+                if !caller.data_mut().check(PluginPermission::Storage) {
+                    return ERR_DENIED;
+                }
+                let Some(key) = read_guest_str(&mut caller, key_ptr, key_len) else {
+                    return ERR_INVALID;
+                };
+                let Some(value) = caller.data().storage.get(&key).cloned() else {
+                    return ERR_NOT_FOUND;
+                };
+                write_guest(&mut caller, out_ptr, out_cap, value.as_bytes())
+            },
+        )
+        .map_err(map_err)?;
+
+    linker
+        .func_wrap(
+            "host",
+            "storage_set",
+            |mut caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32, val_ptr: i32, val_len: i32| -> i32 {
+                if !caller.data_mut().check(PluginPermission::Storage) {
+                    return ERR_DENIED;
+                }
+                let Some(key) = read_guest_str(&mut caller, key_ptr, key_len) else {
+                    return ERR_INVALID;
+                };
+                let Some(value) = read_guest_str(&mut caller, val_ptr, val_len) else {
+                    return ERR_INVALID;
+                };
+                let state = caller.data_mut();
+                state.storage.insert(key, value);
+                state.storage_dirty = true;
+                0
+            },
+        )
+        .map_err(map_err)?;
+
+    linker
+        .func_wrap(
+            "host",
+            "read_file",
+            |mut caller: Caller<'_, HostState>, path_ptr: i32, path_len: i32, out_ptr: i32, out_cap: i32| -> i32 {
+                if !caller.data_mut().check(PluginPermission::Filesystem) {
+                    return ERR_DENIED;
+                }
+                let Some(path) = read_guest_str(&mut caller, path_ptr, path_len) else {
+                    return ERR_INVALID;
+                };
+                let target = expand_home(&path);
+                if !path_is_granted(&caller.data().manifest, &target) {
+                    let state = caller.data_mut();
+                    if !state.output.denied.contains(&PluginPermission::Filesystem) {
+                        state.output.denied.push(PluginPermission::Filesystem);
+                    }
+                    return ERR_DENIED;
+                }
+                // Read no more than the guest can take; the return value is
+                // still the full size so the plugin can tell it was cut off.
+                let Ok(file) = fs::File::open(&target) else {
+                    return ERR_NOT_FOUND;
+                };
+                let (Ok(meta), Ok(cap)) = (file.metadata(), u64::try_from(out_cap)) else {
+                    return ERR_INVALID;
+                };
+                let mut data = vec![];
+                if file.take(cap).read_to_end(&mut data).is_err() {
+                    return ERR_NOT_FOUND;
+                }
+                match write_guest(&mut caller, out_ptr, out_cap, &data) {
+                    n if n < 0 => n,
+                    _ => i32::try_from(meta.len()).unwrap_or(i32::MAX),
+                }
+            },
+        )
+        .map_err(map_err)?;
+
+    Ok(linker)
+}
+
+fn describe_call_error(e: wasmtime::Error) -> String {
+    match e.downcast_ref::<Trap>() {
+        Some(Trap::OutOfFuel) => "Plugin exceeded its execution budget".to_string(),
+        _ => format!("Plugin failed: {}", e),
+    }
+}
+
+/// The compiled module at `path`, recompiled only when the file changed.
+fn compiled_module(path: &Path) -> Result<Module, String> {
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map_err(|e| format!("Failed to load plugin module: {}", e))?;
+    if let Some((compiled_at, module)) = MODULES.lock().get(path) {
+        if *compiled_at == modified {
+            return Ok(module.clone());
+        }
+    }
+    let module = Module::from_file(&ENGINE, path).map_err(|e| format!("Failed to load plugin module: {}", e))?;
+    MODULES.lock().insert(path.to_path_buf(), (modified, module.clone()));
+    Ok(module)
+}
+
+impl WasmPlugin {
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let manifest = read_manifest(dir)?;
+        let module = compiled_module(&dir.join(&manifest.entry))?;
+        Ok(Self {
+            manifest,
+            dir: dir.to_path_buf(),
+            module,
+        })
+    }
+
+    /// Calls the `search` export with the query. Plugins report matches back
+    /// through the `emit_result` host function.
+    pub fn search(&self, query: &str) -> Result<PluginOutput, String> {
+        self.call("search", query)
+    }
+
+    /// Calls the optional `activate` export with the id of a chosen result.
+    pub fn activate(&self, result_id: &str) -> Result<PluginOutput, String> {
+        self.call("activate", result_id)
+    }
+
+    fn call(&self, export: &str, input: &str) -> Result<PluginOutput, String> {
+        let limits = &self.manifest.limits;
+        let state = HostState {
+            manifest: self.manifest.clone(),
+            dir: self.dir.clone(),
+            storage: load_storage(&self.dir),
+            storage_dirty: false,
+            output: PluginOutput::default(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(limits.memory_bytes)
+                .instances(1)
+                .build(),
+        };
+        let mut store = Store::new(&ENGINE, state);
+        store.limiter(|s| &mut s.limits);
+        store
+            .set_fuel(limits.fuel)
+            .map_err(|e| format!("Failed to set plugin fuel: {}", e))?;
+
+        let linker = LINKER.as_ref().map_err(Clone::clone)?;
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .map_err(describe_call_error)?;
+
+        let Ok(func) = instance.get_typed_func::<(i32, i32), ()>(&mut store, export) else {
+            if export == "search" {
+                return Err("Plugin does not export search(ptr, len)".to_string());
+            }
+            return Ok(PluginOutput::default());
+        };
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|_| "Plugin does not export alloc(len)".to_string())?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| "Plugin does not export memory".to_string())?;
+
+        let bytes = input.as_bytes();
+        let len = i32::try_from(bytes.len()).map_err(|_| "Plugin input too large".to_string())?;
+        let ptr = alloc.call(&mut store, len).map_err(describe_call_error)?;
+        memory
+            .write(&mut store, usize::try_from(ptr).unwrap_or(usize::MAX), bytes)
+            .map_err(|_| "Plugin returned an invalid buffer".to_string())?;
+        func.call(&mut store, (ptr, len)).map_err(describe_call_error)?;
+
+        let state = store.into_data();
+        if state.storage_dirty {
+            if let Ok(content) = serde_json::to_string_pretty(&state.storage) {
+                let _ = fs::write(state.dir.join(STORAGE_FILE), content);
+            }
+        }
+        Ok(state.output)
+    }
+}
+
+fn load_plugins() -> Vec<Result<WasmPlugin, String>> {
+    let Ok(read_dir) = fs::read_dir(plugins_dir()) else {
+        return vec![];
+    };
+    read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .map(|path| {
+            WasmPlugin::load(&path)
+                .inspect_err(|e| tracing::warn!(plugin = %path.display(), "could not load plugin: {}", e))
+        })
+        .collect()
+}
+
+fn open_urls(urls: &[String]) {
+    for url in urls {
+        let _ = Command::new("open").arg(url).spawn();
+    }
+}
+
+#[tauri::command]
+pub fn list_plugins() -> Vec<PluginManifest> {
+    load_plugins()
+        .into_iter()
+        .flatten()
+        .map(|plugin| plugin.manifest)
+        .collect()
+}
+
+/// Results from every plugin. URLs a plugin asks to open while searching are
+/// ignored; only activating a result may open them.
+#[tauri::command]
+pub fn search_plugins(query: String) -> Vec<PluginResult> {
+    let mut results = vec![];
+    for plugin in load_plugins().into_iter().flatten() {
+        match plugin.search(&query) {
+            Ok(output) => results.extend(output.results),
+            Err(e) => tracing::warn!(plugin = %plugin.manifest.id, "plugin search failed: {}", e),
+        }
+    }
+    results
+}
+
+#[tauri::command]
+pub fn activate_plugin_result(plugin_id: String, result_id: String) -> Result<(), AppError> {
+    let plugin = load_plugins()
+        .into_iter()
+        .flatten()
+        .find(|p| p.manifest.id == plugin_id)
+        .ok_or_else(|| AppError::not_found(Resource::Plugin, &plugin_id))?;
+    let output = plugin.activate(&result_id).map_err(AppError::plugin)?;
+    open_urls(&output.opened_urls);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_dir, TempDir};
+
+    fn plugin_dir(name: &str, manifest: &str, wat: &str) -> TempDir {
+        let dir = temp_dir(&format!("plugin_{}", name));
+        fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        fs::write(dir.join("plugin.wasm"), wat).unwrap();
+        dir
+    }
+
+    const ECHO_WAT: &str = r#"(module
+        (import "host" "emit_result" (func $emit (param i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 0) "{\"title\":\"")
+        (func (export "alloc") (param i32) (result i32) i32.const 1024)
+        (func (export "search") (param $p i32) (param $n i32)
+            (memory.copy (i32.const 10) (local.get $p) (local.get $n))
+            (i32.store16 (i32.add (i32.const 10) (local.get $n)) (i32.const 0x7d22))
+            (drop (call $emit (i32.const 0) (i32.add (local.get $n) (i32.const 12))))))"#;
+
+    #[test]
+    fn loads_plugin_and_collects_results() {
+        let dir = plugin_dir(
+            "echo",
+            r#"{"id":"echo","name":"Echo","permissions":["search"]}"#,
+            ECHO_WAT,
+        );
+        let plugin = WasmPlugin::load(&dir).unwrap();
+        let output = plugin.search("hello").unwrap();
+        assert_eq!(output.results.len(), 1);
+        assert_eq!(output.results[0].title, "hello");
+        assert_eq!(output.results[0].plugin_id, "echo");
+        assert!(output.denied.is_empty());
+    }
+
+    #[test]
+    fn reuses_compiled_module_until_the_file_changes() {
+        let dir = plugin_dir(
+            "cache",
+            r#"{"id":"cache","name":"Cache","permissions":["search"]}"#,
+            ECHO_WAT,
+        );
+        let entry = dir.join("plugin.wasm");
+        let compiled_at = || MODULES.lock().get(&entry).map(|(modified, _)| *modified);
+        WasmPlugin::load(&dir).unwrap();
+        let first = compiled_at().unwrap();
+        WasmPlugin::load(&dir).unwrap();
+        assert_eq!(compiled_at(), Some(first));
+
+        let later = first + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&entry).unwrap().set_modified(later).unwrap();
+        let reloaded = WasmPlugin::load(&dir).unwrap();
+        assert_eq!(compiled_at(), Some(later));
+        assert_eq!(reloaded.search("hi").unwrap().results[0].title, "hi");
+    }
+
+    #[test]
+    fn rejects_invalid_manifest_and_module() {
+        let dir = plugin_dir("bad_id", r#"{"id":"../evil","name":"Evil"}"#, ECHO_WAT);
+        assert!(WasmPlugin::load(&dir).is_err());
+
+        let dir = plugin_dir("bad_wasm", r#"{"id":"broken","name":"Broken"}"#, "not wasm");
+        assert!(WasmPlugin::load(&dir).is_err());
+    }
+
+    #[test]
+    fn denies_undeclared_permissions() {
+        let dir = plugin_dir(
+            "denied",
+            r#"{"id":"denied","name":"Denied","permissions":[]}"#,
+            r#"(module
+                (import "host" "emit_result" (func $emit (param i32 i32) (result i32)))
+                (import "host" "open_url" (func $open (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "https://example.com")
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "search") (param i32 i32)
+                    (drop (call $open (i32.const 0) (i32.const 19)))
+                    (drop (call $emit (i32.const 0) (i32.const 19)))))"#,
+        );
+        let output = WasmPlugin::load(&dir).unwrap().search("x").unwrap();
+        assert!(output.opened_urls.is_empty());
+        assert!(output.results.is_empty());
+        assert_eq!(
+            output.denied,
+            vec![PluginPermission::OpenUrl, PluginPermission::Search]
+        );
+    }
+
+    #[test]
+    fn filesystem_access_limited_to_granted_roots() {
+        let dir = plugin_dir(
+            "fs",
+            r#"{"id":"fs","name":"Fs","permissions":["search","filesystem"],"filesystem":[]}"#,
+            r#"(module
+                (import "host" "read_file" (func $read (param i32 i32 i32 i32) (result i32)))
+                (import "host" "emit_result" (func $emit (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{\"title\":\"read\"}")
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "search") (param $p i32) (param $n i32)
+                    (if (i32.ge_s (call $read (local.get $p) (local.get $n) (i32.const 2048) (i32.const 64)) (i32.const 0))
+                        (then (drop (call $emit (i32.const 0) (i32.const 16)))))))"#,
+        );
+        let secret = dir.join(MANIFEST_FILE).to_string_lossy().to_string();
+        let output = WasmPlugin::load(&dir).unwrap().search(&secret).unwrap();
+        assert!(output.results.is_empty());
+        assert_eq!(output.denied, vec![PluginPermission::Filesystem]);
+    }
+
+    #[test]
+    fn reads_granted_files_only() {
+        let granted = temp_dir("plugin_fs_granted");
+        let other = temp_dir("plugin_fs_other");
+        fs::write(granted.join("result.json"), r#"{"title":"from file"}"#).unwrap();
+        fs::write(other.join("result.json"), r#"{"title":"secret"}"#).unwrap();
+        let manifest = serde_json::json!({
+            "id": "reader",
+            "name": "Reader",
+            "permissions": ["search", "filesystem"],
+            "filesystem": [granted.to_string_lossy()],
+        });
+        let dir = plugin_dir(
+            "reader",
+            &manifest.to_string(),
+            r#"(module
+                (import "host" "read_file" (func $read (param i32 i32 i32 i32) (result i32)))
+                (import "host" "emit_result" (func $emit (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "search") (param $p i32) (param $n i32)
+                    (local $len i32)
+                    (local.set $len (call $read (local.get $p) (local.get $n) (i32.const 2048) (i32.const 256)))
+                    (if (i32.gt_s (local.get $len) (i32.const 0))
+                        (then (drop (call $emit (i32.const 2048) (local.get $len)))))))"#,
+        );
+        let plugin = WasmPlugin::load(&dir).unwrap();
+
+        let output = plugin.search(&granted.join("result.json").to_string_lossy()).unwrap();
+        assert_eq!(output.results.len(), 1);
+        assert_eq!(output.results[0].title, "from file");
+        assert!(output.denied.is_empty());
+
+        let output = plugin.search(&other.join("result.json").to_string_lossy()).unwrap();
+        assert!(output.results.is_empty());
+        assert_eq!(output.denied, vec![PluginPermission::Filesystem]);
+    }
+
+    #[test]
+    fn reads_own_storage() {
+        let dir = plugin_dir(
+            "storage",
+            r#"{"id":"storage","name":"Storage","permissions":["search","storage"]}"#,
+            r#"(module
+                (import "host" "storage_get" (func $get (param i32 i32 i32 i32) (result i32)))
+                (import "host" "emit_result" (func $emit (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "saved")
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "search") (param i32 i32)
+                    (local $n i32)
+                    (local.set $n (call $get (i32.const 0) (i32.const 5) (i32.const 2048) (i32.const 256)))
+                    (if (i32.gt_s (local.get $n) (i32.const 0))
+                        (then (drop (call $emit (i32.const 2048) (local.get $n)))))))"#,
+        );
+        fs::write(
+            dir.join(STORAGE_FILE),
+            r#"{"saved":"{\"title\":\"from storage\"}"}"#,
+        )
+        .unwrap();
+        let output = WasmPlugin::load(&dir).unwrap().search("").unwrap();
+        assert_eq!(output.results.len(), 1);
+        assert_eq!(output.results[0].title, "from storage");
+    }
+
+    #[test]
+    fn stops_runaway_plugins() {
+        let dir = plugin_dir(
+            "loop",
+            r#"{"id":"loop","name":"Loop","permissions":["search"],"limits":{"fuel":10000}}"#,
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "search") (param i32 i32) (loop $l (br $l))))"#,
+        );
+        let err = WasmPlugin::load(&dir).unwrap().search("x").unwrap_err();
+        assert!(err.contains("execution budget"));
+    }
+
+    #[test]
+    fn caps_plugin_memory() {
+        let dir = plugin_dir(
+            "memory",
+            r#"{"id":"memory","name":"Memory","permissions":["search"],"limits":{"memory_bytes":131072}}"#,
+            r#"(module
+                (import "host" "emit_result" (func $emit (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{\"title\":\"grew\"}")
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "search") (param i32 i32)
+                    (if (i32.ne (memory.grow (i32.const 16)) (i32.const -1))
+                        (then (drop (call $emit (i32.const 0) (i32.const 16)))))))"#,
+        );
+        let output = WasmPlugin::load(&dir).unwrap().search("x").unwrap();
+        assert!(output.results.is_empty());
+    }
+}
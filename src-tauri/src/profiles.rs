@@ -0,0 +1,262 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::config::{self, get_config_path, load_config, save_config, update_config, AppConfig};
+use crate::error::{AppError, IoAction, Resource};
+
+const DEFAULT_PROFILE: &str = "Default";
+const ACTIVE_FILE: &str = "active";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+}
+
+/// Inactive profiles live in `profiles/<name>.json`; the active one is always
+/// `config.json`, so everything else keeps reading a single file.
+fn profiles_dir() -> PathBuf {
+    let mut dir = get_config_path();
+    dir.push("profiles");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+fn profile_path(name: &str) -> PathBuf {
+    profiles_dir().join(format!("{}.json", name))
+}
+
+/// Profile names must be 1-64 characters and usable as a file name.
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.len() > 64 || trimmed.starts_with('.') || trimmed.contains(['/', '\\', ':']) {
+        return Err(AppError::InvalidName {
+            resource: Resource::Profile,
+            name: name.to_string(),
+        });
+    }
+    Ok(trimmed)
+}
+
+pub(crate) fn active_profile() -> String {
+    fs::read_to_string(profiles_dir().join(ACTIVE_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub(crate) fn list() -> Vec<ProfileInfo> {
+    let active = active_profile();
+    let mut names: Vec<String> = fs::read_dir(profiles_dir())
+        .map(|read_dir| {
+            read_dir
+                .flatten()
+                .filter_map(|e| {
+                    e.file_name()
+                        .to_string_lossy()
+                        .strip_suffix(".json")
+                        .map(str::to_string)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if !names.contains(&active) {
+        names.push(active.clone());
+    }
+    names.sort_by_key(|n| n.to_lowercase());
+    names
+        .into_iter()
+        .map(|name| ProfileInfo {
+            active: name == active,
+            name,
+        })
+        .collect()
+}
+
+/// Creates a profile from the current config or from defaults.
+pub(crate) fn create(name: &str, copy_current: bool) -> Result<(), AppError> {
+    let name = validate_name(name)?;
+    if name == active_profile() || profile_path(name).exists() {
+        return Err(AppError::AlreadyExists {
+            resource: Resource::Profile,
+            name: name.to_string(),
+        });
+    }
+    let config = if copy_current {
+        load_config()
+    } else {
+        AppConfig::default()
+    };
+    write_profile(&profile_path(name), &config)
+}
+
+fn write_profile(path: &Path, config: &AppConfig) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(config).map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    fs::write(path, content).map_err(|e| AppError::io(IoAction::Write, path, e))
+}
+
+/// Stores the current config under the active profile's name and loads
+/// `name` in its place. Both happen in one config transaction, and the
+/// marker and stored copy only change once the new config is saved, so a
+/// failed switch leaves both profiles on disk.
+pub(crate) fn switch(name: &str) -> Result<AppConfig, AppError> {
+    let name = validate_name(name)?;
+    let active = active_profile();
+    if name == active {
+        return Ok(load_config());
+    }
+    let content =
+        fs::read_to_string(profile_path(name)).map_err(|_| AppError::not_found(Resource::Profile, name))?;
+    let parsed = config::parse_config(&content);
+    if let Some(error) = parsed.error {
+        return Err(AppError::InvalidConfig {
+            source: name.to_string(),
+            detail: error.to_string(),
+        });
+    }
+
+    let active_path = profile_path(&active);
+    let outgoing = update_config(|config| {
+        write_profile(&active_path, config)?;
+        Ok::<_, AppError>(std::mem::replace(config, parsed.config))
+    })??;
+    let marker = profiles_dir().join(ACTIVE_FILE);
+    if let Err(e) = fs::write(&marker, name) {
+        // Put the outgoing profile back so config.json matches the marker.
+        if let Err(e) = save_config(&outgoing) {
+            tracing::error!(profile = %active, "could not restore the active profile: {}", e);
+        }
+        return Err(AppError::io(IoAction::Write, &marker, e));
+    }
+    if let Err(e) = fs::remove_file(profile_path(name)) {
+        tracing::warn!(profile = name, "could not remove the stored copy of the new active profile: {}", e);
+    }
+    // Undo entries belong to the profile they were made in.
+    crate::history::clear();
+    tracing::info!(from = %active, to = name, "switched profile");
+    Ok(load_config())
+}
+
+pub(crate) fn delete(name: &str) -> Result<(), AppError> {
+    let name = validate_name(name)?;
+    if name == active_profile() {
+        return Err(AppError::ActiveProfile { name: name.to_string() });
+    }
+    fs::remove_file(profile_path(name)).map_err(|_| AppError::not_found(Resource::Profile, name))
+}
+
+#[tauri::command]
+pub fn list_profiles() -> Vec<ProfileInfo> {
+    list()
+}
+
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String, copy_current: Option<bool>) -> Result<(), AppError> {
+    create(&name, copy_current.unwrap_or(false))
+        .inspect_err(|e| tracing::warn!(profile = %name, "could not create profile: {}", e))?;
+    crate::update_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<AppConfig, AppError> {
+    let config = switch(&name).inspect_err(|e| tracing::warn!(profile = %name, "could not switch profile: {}", e))?;
+    crate::apply_config(&app, &config);
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<(), AppError> {
+    delete(&name).inspect_err(|e| tracing::warn!(profile = %name, "could not delete profile: {}", e))?;
+    crate::update_tray_menu(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_test_home;
+
+    fn names() -> Vec<String> {
+        list().into_iter().map(|p| p.name).collect()
+    }
+
+    #[test]
+    fn create_switch_and_delete() {
+        with_test_home(|_| {
+            let mut work = load_config();
+            work.shortcut = "Cmd+W".to_string();
+            work.theme = "Forest".to_string();
+            save_config(&work).unwrap();
+            assert_eq!(list(), vec![ProfileInfo { name: "Default".into(), active: true }]);
+
+            create("Personal", false).unwrap();
+            assert_eq!(names(), vec!["Default", "Personal"]);
+
+            let personal = switch("Personal").unwrap();
+            assert_eq!(personal.shortcut, AppConfig::default().shortcut);
+            assert_eq!(load_config().theme, AppConfig::default().theme);
+            assert_eq!(active_profile(), "Personal");
+
+            let back = switch("Default").unwrap();
+            assert_eq!(back.shortcut, "Cmd+W");
+            assert_eq!(back.theme, "Forest");
+
+            assert!(delete("Default").is_err());
+            delete("Personal").unwrap();
+            assert_eq!(names(), vec!["Default"]);
+        });
+    }
+
+    #[test]
+    fn copies_current_config_when_asked() {
+        with_test_home(|_| {
+            let mut config = load_config();
+            config.user_categories.push("Games".to_string());
+            save_config(&config).unwrap();
+
+            create("Work", true).unwrap();
+            let work = switch("Work").unwrap();
+            assert!(work.user_categories.contains(&"Games".to_string()));
+        });
+    }
+
+    #[test]
+    fn failed_switch_keeps_both_profiles() {
+        with_test_home(|_| {
+            let mut work = load_config();
+            work.shortcut = "Cmd+W".to_string();
+            save_config(&work).unwrap();
+            create("Personal", false).unwrap();
+
+            // A directory in the way of the temp file makes the save fail.
+            let blocker = config::config_file_path().with_extension("json.tmp");
+            fs::create_dir_all(&blocker).unwrap();
+            assert!(switch("Personal").is_err());
+            fs::remove_dir_all(&blocker).unwrap();
+
+            assert_eq!(active_profile(), "Default");
+            assert_eq!(load_config().shortcut, "Cmd+W");
+            assert!(profile_path("Personal").exists());
+
+            let personal = switch("Personal").unwrap();
+            assert_eq!(personal.shortcut, AppConfig::default().shortcut);
+            assert_eq!(switch("Default").unwrap().shortcut, "Cmd+W");
+        });
+    }
+
+    #[test]
+    fn rejects_bad_names_and_duplicates() {
+        with_test_home(|_| {
+            assert!(create("", false).is_err());
+            assert!(create("../escape", false).is_err());
+            assert!(create("Default", false).is_err());
+            create("Work", false).unwrap();
+            assert!(create("Work", false).is_err());
+            assert!(switch("Missing").is_err());
+        });
+    }
+}
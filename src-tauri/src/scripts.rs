@@ -1,10 +1,12 @@
 use std::fs;
 use std::process::Command;
 
-use crate::config::{load_config, save_config, ScriptAction};
+use crate::config::{update_config_labeled, ScriptAction};
+use crate::error::{AppError, IoAction};
 
 #[tauri::command]
-pub fn run_script(command: String, cwd: Option<String>) {
+pub fn run_script(command: String, cwd: Option<String>) -> Result<(), AppError> {
+    tracing::debug!(command = %command, "running script");
     let mut full_command = String::new();
     full_command.push_str("#!/bin/sh\n");
     full_command.push_str("echo 'Running Custom Script...'\n");
@@ -25,26 +27,38 @@ pub fn run_script(command: String, cwd: Option<String>) {
     let file_path =
         temp_dir.join(format!("macappcontrol_script_{}.command", chrono::Utc::now().timestamp_millis()));
 
-    if fs::write(&file_path, full_command).is_ok() {
-        let _ = Command::new("chmod").arg("+x").arg(&file_path).status();
-        let _ = Command::new("open").arg(file_path).spawn();
+    fs::write(&file_path, full_command).map_err(|e| AppError::io(IoAction::Write, &file_path, e))?;
+    let status = Command::new("chmod")
+        .arg("+x")
+        .arg(&file_path)
+        .status()
+        .map_err(|e| AppError::command("chmod", e))?;
+    if !status.success() {
+        return Err(AppError::command("chmod", status));
     }
+    Command::new("open")
+        .arg(file_path)
+        .spawn()
+        .map_err(|e| AppError::command("open", e))?;
+    Ok(())
 }
 
 #[tauri::command]
-pub fn add_script(app: tauri::AppHandle, name: String, command: String, cwd: Option<String>) {
-    let mut config = load_config();
-    config.scripts.push(ScriptAction { name, command, cwd });
-    save_config(&config);
+pub fn add_script(app: tauri::AppHandle, name: String, command: String, cwd: Option<String>) -> Result<(), AppError> {
+    update_config_labeled(format!("Added script {}", name), |config| {
+        config.scripts.push(ScriptAction { name, command, cwd })
+    })?;
     crate::update_tray_menu(&app);
+    Ok(())
 }
 
 #[tauri::command]
-pub fn remove_script(app: tauri::AppHandle, name: String) {
-    let mut config = load_config();
-    config.scripts.retain(|s| s.name != name);
-    save_config(&config);
+pub fn remove_script(app: tauri::AppHandle, name: String) -> Result<(), AppError> {
+    update_config_labeled(format!("Removed script {}", name), |config| {
+        config.scripts.retain(|s| s.name != name)
+    })?;
     crate::update_tray_menu(&app);
+    Ok(())
 }
 
 #[tauri::command]
@@ -54,12 +68,13 @@ pub fn update_script(
     name: String,
     command: String,
     cwd: Option<String>,
-) {
-    let mut config = load_config();
-    config
-        .scripts
-        .retain(|s| s.name != original_name && s.name != name);
-    config.scripts.push(ScriptAction { name, command, cwd });
-    save_config(&config);
+) -> Result<(), AppError> {
+    update_config_labeled(format!("Edited script {}", name), |config| {
+        config
+            .scripts
+            .retain(|s| s.name != original_name && s.name != name);
+        config.scripts.push(ScriptAction { name, command, cwd });
+    })?;
     crate::update_tray_menu(&app);
+    Ok(())
 }
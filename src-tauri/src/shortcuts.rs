@@ -1,9 +1,10 @@
 use tauri::{AppHandle, Manager};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
-use crate::config::{load_config, save_config};
+use crate::config::update_config_labeled;
+use crate::error::AppError;
 
-fn parse_shortcut(s: &str) -> Option<Shortcut> {
+pub(crate) fn parse_shortcut(s: &str) -> Option<Shortcut> {
     let parts: Vec<&str> = s.split('+').collect();
     if parts.len() < 2 {
         return None;
@@ -59,28 +60,35 @@ fn parse_shortcut(s: &str) -> Option<Shortcut> {
 }
 
 pub(crate) fn register_app_shortcut(app: &AppHandle, shortcut_str: &str) {
-    if let Some(shortcut) = parse_shortcut(shortcut_str) {
-        let _ = app.global_shortcut().unregister_all();
-        let app_handle = app.clone();
-        let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
-            if event.state() == ShortcutState::Pressed {
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    if window.is_visible().unwrap_or(false) {
-                        let _ = window.hide();
-                    } else {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+    let Some(shortcut) = parse_shortcut(shortcut_str) else {
+        tracing::warn!(shortcut = shortcut_str, "unsupported shortcut, keeping the current one");
+        return;
+    };
+    let _ = app.global_shortcut().unregister_all();
+    let app_handle = app.clone();
+    let registered = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
+        if event.state() == ShortcutState::Pressed {
+            if let Some(window) = app_handle.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    let _ = window.show();
+                    let _ = window.set_focus();
                 }
             }
-        });
+        }
+    });
+    match registered {
+        Ok(()) => tracing::info!(shortcut = shortcut_str, "registered global shortcut"),
+        Err(e) => tracing::error!(shortcut = shortcut_str, "could not register global shortcut: {}", e),
     }
 }
 
 #[tauri::command]
-pub fn update_shortcut(app_handle: AppHandle, shortcut: String) {
-    let mut config = load_config();
-    config.shortcut = shortcut.clone();
-    save_config(&config);
+pub fn update_shortcut(app_handle: AppHandle, shortcut: String) -> Result<(), AppError> {
+    update_config_labeled(format!("Changed shortcut to {}", shortcut), |config| {
+        config.shortcut = shortcut.clone()
+    })?;
     register_app_shortcut(&app_handle, &shortcut);
+    Ok(())
 }
@@ -0,0 +1,350 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::apps::AppInfo;
+use crate::config::{update_config_labeled, AppConfig};
+use crate::error::AppError;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const CATEGORY_TYPE_PREFIX: &str = "public.app-category.";
+
+/// "public.app-category.developer-tools" -> "developer-tools".
+fn category_type_key(value: &str) -> &str {
+    value.strip_prefix(CATEGORY_TYPE_PREFIX).unwrap_or(value)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rule {
+    BundleIdPrefix { prefix: String },
+    NameRegex { pattern: String },
+    /// The app lives under this folder, e.g. "~/Applications".
+    PathUnder { root: String },
+    /// "arm64" or "x86_64"; universal apps match both.
+    Architecture { arch: String },
+    /// `LSApplicationCategoryType`, with or without the
+    /// "public.app-category." prefix.
+    AppCategoryType { value: String },
+    InstalledWithin { days: u32 },
+}
+
+/// A category whose apps are picked by rules instead of by hand.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SmartCategory {
+    pub category: String,
+    pub rules: Vec<Rule>,
+    /// Require every rule to match instead of any one.
+    #[serde(default)]
+    pub match_all: bool,
+}
+
+enum Compiled {
+    BundleIdPrefix(String),
+    NameRegex(Regex),
+    PathUnder(String),
+    Architecture(String),
+    AppCategoryType(String),
+    InstalledWithin(u64),
+}
+
+impl Compiled {
+    fn new(rule: &Rule) -> Result<Self, AppError> {
+        Ok(match rule {
+            Rule::BundleIdPrefix { prefix } => Self::BundleIdPrefix(prefix.clone()),
+            Rule::NameRegex { pattern } => Self::NameRegex(
+                Regex::new(pattern).map_err(|e| AppError::InvalidPattern {
+                    pattern: pattern.clone(),
+                    detail: e.to_string(),
+                })?,
+            ),
+            Rule::PathUnder { root } => {
+                let root = crate::config::expand_home(root).to_string_lossy().to_string();
+                Self::PathUnder(root.trim_end_matches('/').to_string())
+            }
+            Rule::Architecture { arch } => Self::Architecture(arch.clone()),
+            Rule::AppCategoryType { value } => Self::AppCategoryType(category_type_key(value).to_string()),
+            Rule::InstalledWithin { days } => Self::InstalledWithin(u64::from(*days) * SECONDS_PER_DAY),
+        })
+    }
+
+    fn matches(&self, app: &AppInfo, now: u64) -> bool {
+        match self {
+            Self::BundleIdPrefix(prefix) => app.bundle_id.as_deref().is_some_and(|id| id.starts_with(prefix)),
+            Self::NameRegex(regex) => regex.is_match(&app.name),
+            Self::PathUnder(root) => app
+                .path
+                .strip_prefix(root.as_str())
+                .is_some_and(|rest| rest.starts_with('/')),
+            Self::Architecture(arch) => app.architectures.iter().any(|a| a == arch),
+            Self::AppCategoryType(value) => app
+                .app_category_type
+                .as_deref()
+                .is_some_and(|t| category_type_key(t) == value),
+            Self::InstalledWithin(seconds) => app.date_installed > 0 && now.saturating_sub(app.date_installed) <= *seconds,
+        }
+    }
+}
+
+/// Smart categories with their rules compiled, ready to run over an app
+/// list, plus the `LSApplicationCategoryType` fallback table.
+#[derive(Default)]
+pub(crate) struct RuleSet {
+    categories: Vec<(String, bool, Vec<Compiled>)>,
+    type_map: HashMap<String, String>,
+}
+
+impl RuleSet {
+    /// Compiles every category. Invalid rules fail here rather than
+    /// silently never matching.
+    pub(crate) fn compile(categories: &[SmartCategory]) -> Result<Self, AppError> {
+        let categories = categories
+            .iter()
+            .map(|c| {
+                let rules = c.rules.iter().map(Compiled::new).collect::<Result<Vec<_>, _>>()?;
+                Ok((c.category.clone(), c.match_all, rules))
+            })
+            .collect::<Result<_, AppError>>()?;
+        Ok(Self {
+            categories,
+            ..Self::default()
+        })
+    }
+
+    /// Skips categories that fail to compile and mappings onto categories
+    /// that no longer exist, for configs edited by hand.
+    pub(crate) fn from_config(config: &AppConfig) -> Self {
+        let categories = config
+            .smart_categories
+            .iter()
+            .filter_map(|c| Self::compile(std::slice::from_ref(c)).ok())
+            .flat_map(|set| set.categories)
+            .collect();
+        let type_map = config
+            .category_type_map
+            .iter()
+            .filter(|(_, category)| config.user_categories.contains(category))
+            .map(|(key, category)| (category_type_key(key).to_string(), category.clone()))
+            .collect();
+        Self { categories, type_map }
+    }
+
+    /// Smart categories `app` belongs to, in definition order. A category
+    /// without rules matches nothing.
+    pub(crate) fn matching(&self, app: &AppInfo, now: u64) -> Vec<String> {
+        self.categories
+            .iter()
+            .filter(|(_, match_all, rules)| {
+                !rules.is_empty()
+                    && if *match_all {
+                        rules.iter().all(|r| r.matches(app, now))
+                    } else {
+                        rules.iter().any(|r| r.matches(app, now))
+                    }
+            })
+            .map(|(category, _, _)| category.clone())
+            .collect()
+    }
+
+    /// Fills in category and tags for an app nobody assigned by hand:
+    /// smart categories first, then the app's declared category type.
+    /// Manual assignments always win, including apps explicitly left
+    /// uncategorized.
+    pub(crate) fn apply(&self, app: &mut AppInfo, config: &AppConfig, now: u64) {
+        if config.categories.contains_key(&app.path) {
+            return;
+        }
+        let mut matches = self.matching(app, now);
+        if matches.is_empty() {
+            matches.extend(
+                app.app_category_type
+                    .as_deref()
+                    .and_then(|t| self.type_map.get(category_type_key(t)))
+                    .cloned(),
+            );
+        }
+        app.category = matches.first().cloned();
+        app.tags = matches;
+    }
+}
+
+#[tauri::command]
+pub fn get_smart_categories() -> Vec<SmartCategory> {
+    crate::config::load_config().smart_categories
+}
+
+#[tauri::command]
+pub fn set_smart_categories(categories: Vec<SmartCategory>) -> Result<(), AppError> {
+    RuleSet::compile(&categories)?;
+    update_config_labeled("Changed smart categories", |config| {
+        let mut normalized = Vec::with_capacity(categories.len());
+        for mut smart in categories {
+            smart.category = crate::categories::add(config, &smart.category)?;
+            normalized.push(smart);
+        }
+        config.smart_categories = normalized;
+        Ok(())
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn app(name: &str, path: &str) -> AppInfo {
+        AppInfo {
+            name: name.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn smart(category: &str, rules: Vec<Rule>) -> SmartCategory {
+        SmartCategory {
+            category: category.to_string(),
+            rules,
+            match_all: false,
+        }
+    }
+
+    fn matches(rule: Rule, app: &AppInfo) -> bool {
+        let set = RuleSet::compile(&[smart("Test", vec![rule])]).unwrap();
+        !set.matching(app, NOW).is_empty()
+    }
+
+    #[test]
+    fn evaluates_each_rule_kind() {
+        let mut idea = app("IntelliJ IDEA", "/Applications/IntelliJ IDEA.app");
+        idea.bundle_id = Some("com.jetbrains.intellij".into());
+        idea.app_category_type = Some("public.app-category.developer-tools".into());
+        idea.architectures = vec!["x86_64".into(), "arm64".into()];
+        idea.date_installed = NOW - 3 * SECONDS_PER_DAY;
+
+        assert!(matches(Rule::BundleIdPrefix { prefix: "com.jetbrains.".into() }, &idea));
+        assert!(!matches(Rule::BundleIdPrefix { prefix: "com.apple.".into() }, &idea));
+        assert!(matches(Rule::NameRegex { pattern: "^IntelliJ".into() }, &idea));
+        assert!(!matches(Rule::NameRegex { pattern: "(?i)^pycharm".into() }, &idea));
+        assert!(matches(Rule::PathUnder { root: "/Applications/".into() }, &idea));
+        assert!(!matches(Rule::PathUnder { root: "/Applications/IntelliJ".into() }, &idea));
+        assert!(matches(Rule::Architecture { arch: "arm64".into() }, &idea));
+        assert!(matches(Rule::AppCategoryType { value: "developer-tools".into() }, &idea));
+        assert!(matches(
+            Rule::AppCategoryType {
+                value: "public.app-category.developer-tools".into()
+            },
+            &idea
+        ));
+        assert!(matches(Rule::InstalledWithin { days: 7 }, &idea));
+        assert!(!matches(Rule::InstalledWithin { days: 2 }, &idea));
+
+        let bare = app("Notes", "/System/Applications/Notes.app");
+        assert!(!matches(Rule::BundleIdPrefix { prefix: "".into() }, &bare));
+        assert!(!matches(Rule::Architecture { arch: "arm64".into() }, &bare));
+        assert!(!matches(Rule::InstalledWithin { days: 7 }, &bare));
+    }
+
+    #[test]
+    fn match_all_requires_every_rule() {
+        let mut rosetta = app("Old Tool", "/Applications/Old Tool.app");
+        rosetta.architectures = vec!["x86_64".into()];
+        let rules = vec![
+            Rule::Architecture { arch: "x86_64".into() },
+            Rule::NameRegex { pattern: "Editor".into() },
+        ];
+        let any = RuleSet::compile(&[smart("Intel", rules.clone())]).unwrap();
+        assert_eq!(any.matching(&rosetta, NOW), vec!["Intel"]);
+        let all = RuleSet::compile(&[SmartCategory {
+            match_all: true,
+            ..smart("Intel", rules)
+        }])
+        .unwrap();
+        assert!(all.matching(&rosetta, NOW).is_empty());
+        let empty = RuleSet::compile(&[smart("Nothing", vec![])]).unwrap();
+        assert!(empty.matching(&rosetta, NOW).is_empty());
+    }
+
+    #[test]
+    fn manual_assignments_win() {
+        let mut config = AppConfig::default();
+        config.categories.insert("/Applications/Rider.app".into(), "Work".into());
+        let set = RuleSet::compile(&[
+            smart("JetBrains", vec![Rule::BundleIdPrefix { prefix: "com.jetbrains.".into() }]),
+            smart("Recent", vec![Rule::InstalledWithin { days: 7 }]),
+        ])
+        .unwrap();
+
+        let mut rider = app("Rider", "/Applications/Rider.app");
+        rider.bundle_id = Some("com.jetbrains.rider".into());
+        rider.category = Some("Work".into());
+        set.apply(&mut rider, &config, NOW);
+        assert_eq!(rider.category.as_deref(), Some("Work"));
+
+        let mut goland = app("GoLand", "/Applications/GoLand.app");
+        goland.bundle_id = Some("com.jetbrains.goland".into());
+        goland.date_installed = NOW - 60;
+        set.apply(&mut goland, &config, NOW);
+        assert_eq!(goland.category.as_deref(), Some("JetBrains"));
+        assert_eq!(goland.tags, vec!["JetBrains", "Recent"]);
+
+        // An empty assignment keeps the app out of every category.
+        config.categories.insert(goland.path.clone(), String::new());
+        let mut goland = app("GoLand", "/Applications/GoLand.app");
+        goland.bundle_id = Some("com.jetbrains.goland".into());
+        set.apply(&mut goland, &config, NOW);
+        assert_eq!(goland.category, None);
+        assert!(goland.tags.is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_declared_category_type() {
+        let mut config = AppConfig::default();
+        let mut xcode = app("Xcode", "/Applications/Xcode.app");
+        xcode.app_category_type = Some("public.app-category.developer-tools".into());
+        let mut game = app("Chess", "/System/Applications/Chess.app");
+        game.app_category_type = Some("public.app-category.board-games".into());
+
+        let set = RuleSet::from_config(&config);
+        set.apply(&mut xcode, &config, NOW);
+        set.apply(&mut game, &config, NOW);
+        assert_eq!(xcode.category.as_deref(), Some("Development"));
+        assert_eq!(xcode.tags, vec!["Development"]);
+        assert_eq!(game.category, None);
+
+        // User edits to the table win over the defaults, and mappings onto
+        // removed categories are ignored.
+        config.user_categories.push("Games".into());
+        config.category_type_map.insert("board-games".into(), "Games".into());
+        config.category_type_map.insert("developer-tools".into(), "Gone".into());
+        let set = RuleSet::from_config(&config);
+        set.apply(&mut xcode, &config, NOW);
+        set.apply(&mut game, &config, NOW);
+        assert_eq!(xcode.category, None);
+        assert_eq!(game.category.as_deref(), Some("Games"));
+
+        // Smart categories and manual assignments take precedence.
+        config.smart_categories = vec![smart("Apple", vec![Rule::NameRegex { pattern: "^X".into() }])];
+        config.categories.insert(game.path.clone(), "Social".into());
+        game.category = Some("Social".into());
+        let set = RuleSet::from_config(&config);
+        set.apply(&mut xcode, &config, NOW);
+        set.apply(&mut game, &config, NOW);
+        assert_eq!(xcode.category.as_deref(), Some("Apple"));
+        assert_eq!(game.category.as_deref(), Some("Social"));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(RuleSet::compile(&[smart("Bad", vec![Rule::NameRegex { pattern: "(".into() }])]).is_err());
+        let config = AppConfig {
+            smart_categories: vec![
+                smart("Bad", vec![Rule::NameRegex { pattern: "(".into() }]),
+                smart("Good", vec![Rule::NameRegex { pattern: ".".into() }]),
+            ],
+            ..AppConfig::default()
+        };
+        let set = RuleSet::from_config(&config);
+        assert_eq!(set.matching(&app("Any", "/Any.app"), NOW), vec!["Good"]);
+    }
+}
@@ -0,0 +1,742 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::categories;
+use crate::config::{self, get_config_path, update_config, AppConfig, CategoryNode, ScriptAction};
+use crate::error::{AppError, IoAction, Resource};
+
+const SHARED_FILE: &str = "macappcontrol-config.json";
+const BASE_FILE: &str = "sync-base.json";
+
+/// Fields with dedicated merge rules; every other field is merged as a scalar.
+const MERGED_FIELDS: [&str; 7] = [
+    "categories",
+    "tags",
+    "usage_counts",
+    "user_categories",
+    "category_order",
+    "scripts",
+    "category_type_map",
+];
+/// Fields that only make sense on this machine. `wallpaper` is an absolute
+/// path into this machine's wallpaper folder.
+const LOCAL_FIELDS: [&str; 6] = [
+    "schema_version",
+    "revision",
+    "sync_folder",
+    "incognito",
+    "log_level",
+    "wallpaper",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    Unchanged,
+    Pushed,
+    Pulled,
+    Merged,
+}
+
+/// A value both machines changed differently since the last sync. The local
+/// value is kept until the user resolves it.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SyncConflict {
+    pub field: String,
+    pub key: Option<String>,
+    pub local: Value,
+    pub remote: Value,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncReport {
+    pub status: SyncStatus,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+/// Which value a conflicting field takes in a merge result.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolve {
+    Local,
+    Remote,
+    Base,
+}
+
+struct Merger {
+    resolve: Resolve,
+    conflicts: Vec<SyncConflict>,
+}
+
+impl Merger {
+    /// Three-way merge of one value. `None` means absent on that side.
+    fn merge<T: PartialEq + Clone + Serialize>(
+        &mut self,
+        field: &str,
+        key: Option<&str>,
+        base: Option<&T>,
+        local: Option<&T>,
+        remote: Option<&T>,
+    ) -> Option<T> {
+        if local == remote || base == remote {
+            return local.cloned();
+        }
+        if base == local {
+            return remote.cloned();
+        }
+        self.conflicts.push(SyncConflict {
+            field: field.to_string(),
+            key: key.map(str::to_string),
+            local: serde_json::to_value(local).unwrap_or_default(),
+            remote: serde_json::to_value(remote).unwrap_or_default(),
+        });
+        match self.resolve {
+            Resolve::Local => local.cloned(),
+            Resolve::Remote => remote.cloned(),
+            Resolve::Base => base.cloned(),
+        }
+    }
+
+    fn merge_map<V: PartialEq + Clone + Serialize>(
+        &mut self,
+        field: &str,
+        base: Option<&HashMap<String, V>>,
+        local: &HashMap<String, V>,
+        remote: &HashMap<String, V>,
+    ) -> HashMap<String, V> {
+        let keys: BTreeSet<&String> = local
+            .keys()
+            .chain(remote.keys())
+            .chain(base.into_iter().flat_map(|b| b.keys()))
+            .collect();
+        keys.into_iter()
+            .filter_map(|k| {
+                let b = base.and_then(|b| b.get(k));
+                self.merge(field, Some(k), b, local.get(k), remote.get(k))
+                    .map(|v| (k.clone(), v))
+            })
+            .collect()
+    }
+}
+
+/// Union of two ordered lists that honours removals made on either side
+/// since `base`. The side that reordered wins (local if both did), and the
+/// other side's additions are appended.
+fn merge_list<T: PartialEq + Eq + Hash + Clone>(base: Option<&[T]>, local: &[T], remote: &[T]) -> Vec<T> {
+    let (first, second) = if base == Some(local) {
+        (remote, local)
+    } else {
+        (local, remote)
+    };
+    let removed = |item: &T, other: &[T]| base.is_some_and(|b| b.contains(item)) && !other.contains(item);
+    let mut merged: Vec<T> = first
+        .iter()
+        .filter(|item| !removed(item, second))
+        .cloned()
+        .collect();
+    for item in second {
+        if !merged.contains(item) && !removed(item, first) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+/// Merges the sidebar trees path by path, so categories added on either
+/// machine appear under their parents and removals on either side stick.
+pub(crate) fn merge_category_order(
+    base: Option<&[CategoryNode]>,
+    local: &[CategoryNode],
+    remote: &[CategoryNode],
+) -> Vec<CategoryNode> {
+    let paths = merge_list(
+        base.map(categories::flatten).as_deref(),
+        &categories::flatten(local),
+        &categories::flatten(remote),
+    );
+    let mut tree = vec![];
+    for path in &paths {
+        categories::ensure_node(&mut tree, path);
+    }
+    tree
+}
+
+fn merge_scripts(
+    merger: &mut Merger,
+    base: Option<&[ScriptAction]>,
+    local: &[ScriptAction],
+    remote: &[ScriptAction],
+) -> Vec<ScriptAction> {
+    let names: Vec<String> = merge_list(
+        base.map(|b| b.iter().map(|s| s.name.clone()).collect::<Vec<_>>())
+            .as_deref(),
+        &local.iter().map(|s| s.name.clone()).collect::<Vec<_>>(),
+        &remote.iter().map(|s| s.name.clone()).collect::<Vec<_>>(),
+    );
+    let find = |list: &[ScriptAction], name: &str| list.iter().find(|s| s.name == name).cloned();
+    names
+        .iter()
+        .filter_map(|name| {
+            let b = base.and_then(|b| find(b, name));
+            let l = find(local, name);
+            let r = find(remote, name);
+            merger.merge("scripts", Some(name), b.as_ref(), l.as_ref(), r.as_ref())
+        })
+        .collect()
+}
+
+/// Launches recorded on each machine since the last sync are added together.
+/// A count that dropped below the base was cleared or pruned on that
+/// machine, so the lower count wins and cleared apps stay cleared.
+fn merge_usage(
+    base: Option<&HashMap<String, u32>>,
+    local: &HashMap<String, u32>,
+    remote: &HashMap<String, u32>,
+) -> HashMap<String, u32> {
+    local
+        .keys()
+        .chain(remote.keys())
+        .map(|k| {
+            let l = local.get(k).copied().unwrap_or(0);
+            let r = remote.get(k).copied().unwrap_or(0);
+            let count = match base.and_then(|b| b.get(k)).copied() {
+                Some(b) if l < b || r < b => l.min(r),
+                Some(b) => l.saturating_add(r) - b,
+                None => l.max(r),
+            };
+            (k.clone(), count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// Configs written by builds without tags only carry `categories`.
+fn with_tags(config: &AppConfig) -> AppConfig {
+    let mut config = config.clone();
+    categories::reconcile_tags(&mut config);
+    config
+}
+
+pub(crate) fn merge_configs(
+    base: Option<&AppConfig>,
+    local: &AppConfig,
+    remote: &AppConfig,
+    resolve: Resolve,
+) -> (AppConfig, Vec<SyncConflict>) {
+    let (base, local, remote) = (base.map(with_tags), with_tags(local), with_tags(remote));
+    let (base, local, remote) = (base.as_ref(), &local, &remote);
+    let mut merger = Merger {
+        resolve,
+        conflicts: vec![],
+    };
+
+    let to_value = |c: &AppConfig| serde_json::to_value(c).unwrap_or_default();
+    let (base_v, local_v, remote_v) = (base.map(to_value), to_value(local), to_value(remote));
+    let mut merged_v = local_v.clone();
+    if let (Some(local_fields), Some(remote_fields)) = (local_v.as_object(), remote_v.as_object()) {
+        for (key, local_field) in local_fields {
+            if MERGED_FIELDS.contains(&key.as_str()) || LOCAL_FIELDS.contains(&key.as_str()) {
+                continue;
+            }
+            let base_field = base_v.as_ref().and_then(|b| b.get(key));
+            if let Some(v) = merger.merge(key, None, base_field, Some(local_field), remote_fields.get(key)) {
+                merged_v[key] = v;
+            }
+        }
+    }
+    let mut merged: AppConfig = serde_json::from_value(merged_v).unwrap_or_else(|_| local.clone());
+
+    merged.categories = merger.merge_map(
+        "categories",
+        base.map(|b| &b.categories),
+        &local.categories,
+        &remote.categories,
+    );
+    let conflicting_apps: Vec<Option<String>> = merger
+        .conflicts
+        .iter()
+        .filter(|c| c.field == "categories")
+        .map(|c| c.key.clone())
+        .collect();
+    merged.tags = merger.merge_map("tags", base.map(|b| &b.tags), &local.tags, &remote.tags);
+    // A conflicting category already covers that app's first tag.
+    merger
+        .conflicts
+        .retain(|c| c.field != "tags" || !conflicting_apps.contains(&c.key));
+    categories::reconcile_tags(&mut merged);
+    merged.category_type_map = merger.merge_map(
+        "category_type_map",
+        base.map(|b| &b.category_type_map),
+        &local.category_type_map,
+        &remote.category_type_map,
+    );
+    merged.usage_counts = merge_usage(base.map(|b| &b.usage_counts), &local.usage_counts, &remote.usage_counts);
+    merged.user_categories = merge_list(
+        base.map(|b| b.user_categories.as_slice()),
+        &local.user_categories,
+        &remote.user_categories,
+    );
+    merged.category_order = merge_category_order(
+        base.map(|b| b.category_order.as_slice()),
+        &local.category_order,
+        &remote.category_order,
+    );
+    merged.scripts = merge_scripts(
+        &mut merger,
+        base.map(|b| b.scripts.as_slice()),
+        &local.scripts,
+        &remote.scripts,
+    );
+    (merged, merger.conflicts)
+}
+
+fn read_config(path: &Path) -> Result<Option<AppConfig>, AppError> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let parsed = config::parse_config(&content);
+    match parsed.error {
+        Some(error) => Err(AppError::InvalidConfig {
+            source: path.display().to_string(),
+            detail: error.to_string(),
+        }),
+        None => Ok(Some(parsed.config)),
+    }
+}
+
+fn write_config(path: &Path, config: &impl Serialize) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(config).map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| AppError::io(IoAction::Write, path, e))
+}
+
+/// The shared copy leaves out this machine's settings. `schema_version`
+/// stays, since readers need it to migrate the file.
+fn write_shared(path: &Path, config: &AppConfig) -> Result<(), AppError> {
+    let mut value = serde_json::to_value(config).map_err(|e| AppError::io(IoAction::Write, path, e))?;
+    if let Some(fields) = value.as_object_mut() {
+        for key in LOCAL_FIELDS.iter().filter(|key| **key != "schema_version") {
+            fields.remove(*key);
+        }
+    }
+    write_config(path, &value)
+}
+
+fn same(a: &AppConfig, b: &AppConfig) -> bool {
+    let strip = |c: &AppConfig| {
+        let mut v = serde_json::to_value(c).unwrap_or_default();
+        if let Some(fields) = v.as_object_mut() {
+            for key in LOCAL_FIELDS {
+                fields.remove(key);
+            }
+        }
+        v
+    };
+    strip(a) == strip(b)
+}
+
+/// The outcome of a sync before anything is written: the config this
+/// machine should use, the new shared copy if it changed, and the new base.
+struct SyncPlan {
+    merged: AppConfig,
+    shared: Option<AppConfig>,
+    base: AppConfig,
+    report: SyncReport,
+}
+
+impl SyncPlan {
+    fn write(&self, base_path: &Path, shared_dir: &Path) -> Result<(), AppError> {
+        if let Some(shared) = &self.shared {
+            write_shared(&shared_dir.join(SHARED_FILE), shared)?;
+        }
+        write_config(base_path, &self.base)
+    }
+}
+
+/// Merges `local` with the copy in `shared_dir`, using `base_path` to
+/// remember what both sides agreed on last time. Only reads files.
+///
+/// Without `prefer`, conflicting fields stay as they are on each side and
+/// keep their old base value, so they are reported again on every sync until
+/// resolved by syncing with a preferred side.
+fn plan_sync(
+    local: &AppConfig,
+    base_path: &Path,
+    shared_dir: &Path,
+    prefer: Option<Resolve>,
+) -> Result<SyncPlan, AppError> {
+    if !shared_dir.is_dir() {
+        return Err(AppError::not_found(Resource::Folder, shared_dir.display().to_string()));
+    }
+    let local = &with_tags(local);
+    let Some(remote) = read_config(&shared_dir.join(SHARED_FILE))? else {
+        return Ok(SyncPlan {
+            merged: local.clone(),
+            shared: Some(local.clone()),
+            base: local.clone(),
+            report: SyncReport {
+                status: SyncStatus::Pushed,
+                conflicts: vec![],
+            },
+        });
+    };
+    let base = read_config(base_path)
+        .inspect_err(|e| tracing::warn!("ignoring unreadable sync base: {}", e))
+        .ok()
+        .flatten();
+
+    let merge = |resolve| merge_configs(base.as_ref(), local, &remote, resolve);
+    let (mut merged, conflicts) = merge(prefer.unwrap_or(Resolve::Local));
+    let (shared, _) = merge(prefer.unwrap_or(Resolve::Remote));
+    let (next_base, _) = merge(prefer.unwrap_or(Resolve::Base));
+    merged.schema_version = local.schema_version;
+    merged.sync_folder = local.sync_folder.clone();
+    let conflicts = if prefer.is_some() { vec![] } else { conflicts };
+
+    let local_changed = !same(&merged, local);
+    let remote_changed = !same(&shared, &remote);
+    let status = match (local_changed, remote_changed) {
+        (false, false) => SyncStatus::Unchanged,
+        (false, true) => SyncStatus::Pushed,
+        (true, false) => SyncStatus::Pulled,
+        (true, true) => SyncStatus::Merged,
+    };
+    Ok(SyncPlan {
+        merged,
+        shared: remote_changed.then_some(shared),
+        base: next_base,
+        report: SyncReport { status, conflicts },
+    })
+}
+
+fn base_path() -> PathBuf {
+    get_config_path().join(BASE_FILE)
+}
+
+/// Forgets the last agreed state; a new folder means a new sync history.
+pub(crate) fn reset_base() {
+    if let Err(e) = fs::remove_file(base_path()) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("could not reset sync base: {}", e);
+        }
+    }
+}
+
+/// Times a sync is retried when the config changes while the shared folder
+/// is read.
+const SYNC_ATTEMPTS: usize = 3;
+
+/// Syncs the active config with its sync folder, if one is set. The folder,
+/// which may be a slow network or cloud drive, is read and written without
+/// holding the config lock; the merged config is committed only if nothing
+/// changed meanwhile, and the shared copy and base are written after that.
+pub(crate) fn sync_active(prefer: Option<Resolve>) -> Result<Option<(AppConfig, SyncReport)>, AppError> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let local = config::load_config();
+        let Some(folder) = local.sync_folder.clone() else {
+            return Ok(None);
+        };
+        let shared_dir = config::expand_home(&folder);
+        let plan = plan_sync(&local, &base_path(), &shared_dir, prefer)
+            .inspect_err(|e| tracing::warn!(folder = %folder, "sync failed: {}", e))?;
+        let mut merged = plan.merged.clone();
+        merged.revision = local.revision;
+        match config::save_if_current(merged) {
+            Err(AppError::StaleConfig { .. }) if attempt < SYNC_ATTEMPTS => continue,
+            Err(e) => {
+                tracing::warn!(folder = %folder, "sync failed: {}", e);
+                return Err(e);
+            }
+            Ok(_) => {}
+        }
+        plan.write(&base_path(), &shared_dir)
+            .inspect_err(|e| tracing::warn!(folder = %folder, "could not write sync files: {}", e))?;
+        let report = plan.report;
+        tracing::info!(status = ?report.status, conflicts = report.conflicts.len(), "synced config");
+        if report.status == SyncStatus::Pulled || report.status == SyncStatus::Merged {
+            // Undo entries were made against values the sync may have replaced.
+            crate::history::clear();
+        }
+        return Ok(Some((config::load_config(), report)));
+    }
+}
+
+#[tauri::command]
+pub fn set_sync_folder(path: Option<String>) -> Result<(), AppError> {
+    update_config(|config| config.sync_folder = path.filter(|p| !p.trim().is_empty()))?;
+    reset_base();
+    Ok(())
+}
+
+/// Syncs with the shared folder. Pass `prefer` to resolve all reported
+/// conflicts in favour of one side.
+#[tauri::command]
+pub fn sync_config(app: AppHandle, prefer: Option<Resolve>) -> Result<Option<SyncReport>, AppError> {
+    let Some((config, report)) = sync_active(prefer)? else {
+        return Ok(None);
+    };
+    if report.status == SyncStatus::Pulled || report.status == SyncStatus::Merged {
+        crate::apply_config(&app, &config);
+    }
+    Ok(Some(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_dir, with_test_home};
+
+    /// Syncs `local` with the copy in `shared_dir` and writes the shared copy
+    /// and base. Returns the config this machine should use from now on.
+    fn sync_with(
+        local: &AppConfig,
+        base_path: &Path,
+        shared_dir: &Path,
+        prefer: Option<Resolve>,
+    ) -> Result<(AppConfig, SyncReport), AppError> {
+        let plan = plan_sync(local, base_path, shared_dir, prefer)?;
+        plan.write(base_path, shared_dir)?;
+        Ok((plan.merged, plan.report))
+    }
+
+    fn script(name: &str, command: &str) -> ScriptAction {
+        ScriptAction {
+            name: name.to_string(),
+            command: command.to_string(),
+            cwd: None,
+        }
+    }
+
+    /// One simulated machine: its own config and its own sync base.
+    struct Machine {
+        config: AppConfig,
+        base: PathBuf,
+    }
+
+    impl Machine {
+        fn new(dir: &Path, name: &str) -> Self {
+            Self {
+                config: AppConfig::default(),
+                base: dir.join(format!("{}-base.json", name)),
+            }
+        }
+
+        fn sync(&mut self, shared: &Path) -> SyncReport {
+            self.sync_preferring(shared, None)
+        }
+
+        fn sync_preferring(&mut self, shared: &Path, prefer: Option<Resolve>) -> SyncReport {
+            let (merged, report) = sync_with(&self.config, &self.base, shared, prefer).unwrap();
+            self.config = merged;
+            report
+        }
+    }
+
+    #[test]
+    fn first_sync_pushes_then_other_machine_pulls() {
+        let (dir, shared) = (temp_dir("sync_machines"), temp_dir("sync_shared"));
+        let mut a = Machine::new(&dir, "a");
+        let mut b = Machine::new(&dir, "b");
+        a.config.scripts.push(script("build", "make"));
+
+        assert_eq!(a.sync(&shared).status, SyncStatus::Pushed);
+        let report = b.sync(&shared);
+        assert_eq!(report.status, SyncStatus::Pulled);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(b.config.scripts, a.config.scripts);
+        assert_eq!(a.sync(&shared).status, SyncStatus::Unchanged);
+    }
+
+    #[test]
+    fn merges_independent_changes_from_both_machines() {
+        let (dir, shared) = (temp_dir("sync_machines"), temp_dir("sync_shared"));
+        let mut a = Machine::new(&dir, "a");
+        let mut b = Machine::new(&dir, "b");
+        a.config.scripts.push(script("old", "echo old"));
+        a.config.usage_counts.insert("/Slack.app".into(), 10);
+        a.sync(&shared);
+        b.sync(&shared);
+
+        a.config.scripts.push(script("lint", "npm run lint"));
+        a.config.user_categories.push("Games".into());
+        a.config.categories.insert("/Steam.app".into(), "Games".into());
+        *a.config.usage_counts.get_mut("/Slack.app").unwrap() += 3;
+        a.config.theme = "Forest".into();
+
+        b.config.scripts.retain(|s| s.name != "old");
+        b.config.scripts.push(script("test", "npm test"));
+        b.config.user_categories.retain(|c| c != "Social");
+        *b.config.usage_counts.get_mut("/Slack.app").unwrap() += 2;
+
+        assert_eq!(a.sync(&shared).status, SyncStatus::Pushed);
+        let report = b.sync(&shared);
+        assert_eq!(report.status, SyncStatus::Merged);
+        assert!(report.conflicts.is_empty());
+
+        let names: Vec<&str> = b.config.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["test", "lint"]);
+        assert!(b.config.user_categories.contains(&"Games".to_string()));
+        assert!(!b.config.user_categories.contains(&"Social".to_string()));
+        assert_eq!(b.config.categories["/Steam.app"], "Games");
+        assert_eq!(b.config.usage_counts["/Slack.app"], 15);
+        assert_eq!(b.config.theme, "Forest");
+
+        assert_eq!(a.sync(&shared).status, SyncStatus::Pulled);
+        assert!(same(&a.config, &b.config));
+    }
+
+    #[test]
+    fn reports_conflicts_and_keeps_local_values() {
+        let (dir, shared) = (temp_dir("sync_machines"), temp_dir("sync_shared"));
+        let mut a = Machine::new(&dir, "a");
+        let mut b = Machine::new(&dir, "b");
+        a.config.scripts.push(script("deploy", "./deploy.sh"));
+        a.sync(&shared);
+        b.sync(&shared);
+
+        a.config.shortcut = "Cmd+K".into();
+        a.config.scripts[0].command = "./deploy.sh --prod".into();
+        a.config.categories.insert("/Figma.app".into(), "Design".into());
+        b.config.shortcut = "Ctrl+Space".into();
+        b.config.scripts[0].command = "./deploy.sh --staging".into();
+        b.config.categories.insert("/Figma.app".into(), "Work".into());
+
+        a.sync(&shared);
+        let report = b.sync(&shared);
+        let fields: Vec<&str> = report.conflicts.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["shortcut", "categories", "scripts"]);
+        assert_eq!(report.conflicts[1].key.as_deref(), Some("/Figma.app"));
+        assert_eq!(b.config.shortcut, "Ctrl+Space");
+        assert_eq!(b.config.scripts[0].command, "./deploy.sh --staging");
+        assert_eq!(b.config.categories["/Figma.app"], "Work");
+
+        // Neither side is overwritten, so the conflict is reported again.
+        assert_eq!(a.sync(&shared).status, SyncStatus::Unchanged);
+        assert_eq!(a.config.shortcut, "Cmd+K");
+        assert_eq!(b.sync(&shared).conflicts.len(), 3);
+
+        let resolved = b.sync_preferring(&shared, Some(Resolve::Remote));
+        assert!(resolved.conflicts.is_empty());
+        assert_eq!(b.config.shortcut, "Cmd+K");
+        assert!(b.sync(&shared).conflicts.is_empty());
+    }
+
+    #[test]
+    fn usage_sums_saturate() {
+        let counts = |n: u32| HashMap::from([("/Slack.app".to_string(), n)]);
+        let merged = merge_usage(Some(&counts(0)), &counts(u32::MAX), &counts(2));
+        assert_eq!(merged["/Slack.app"], u32::MAX);
+    }
+
+    #[test]
+    fn cleared_usage_stays_cleared_on_every_machine() {
+        let (dir, shared) = (temp_dir("sync_machines"), temp_dir("sync_shared"));
+        let mut a = Machine::new(&dir, "a");
+        let mut b = Machine::new(&dir, "b");
+        a.config.usage_counts.insert("/Slack.app".into(), 10);
+        a.config.usage_counts.insert("/Xcode.app".into(), 8);
+        a.sync(&shared);
+        b.sync(&shared);
+
+        a.config.usage_counts.clear();
+        *b.config.usage_counts.get_mut("/Slack.app").unwrap() += 2;
+        assert_eq!(a.sync(&shared).status, SyncStatus::Pushed);
+        let remote = read_config(&shared.join(SHARED_FILE)).unwrap().unwrap();
+        assert!(remote.usage_counts.is_empty());
+
+        b.sync(&shared);
+        assert!(b.config.usage_counts.is_empty());
+        assert_eq!(a.sync(&shared).status, SyncStatus::Unchanged);
+        assert!(a.config.usage_counts.is_empty());
+
+        // Pruning lowers a count the same way.
+        a.config.usage_counts.insert("/Slack.app".into(), 5);
+        a.sync(&shared);
+        b.sync(&shared);
+        b.config.usage_counts.insert("/Slack.app".into(), 1);
+        a.config.usage_counts.insert("/Slack.app".into(), 6);
+        b.sync(&shared);
+        a.sync(&shared);
+        assert_eq!(a.config.usage_counts["/Slack.app"], 1);
+    }
+
+    #[test]
+    fn merges_sidebar_order_trees_from_both_machines() {
+        let (dir, shared) = (temp_dir("sync_machines"), temp_dir("sync_shared"));
+        let mut a = Machine::new(&dir, "a");
+        let mut b = Machine::new(&dir, "b");
+        a.sync(&shared);
+        b.sync(&shared);
+
+        categories::add(&mut a.config, "Development/Databases").unwrap();
+        categories::add(&mut b.config, "Development/Editors").unwrap();
+        categories::add(&mut b.config, "Games").unwrap();
+        categories::remove(&mut b.config, "Social");
+
+        a.sync(&shared);
+        assert_eq!(b.sync(&shared).status, SyncStatus::Merged);
+        a.sync(&shared);
+        for machine in [&a, &b] {
+            let paths = categories::flatten(&machine.config.category_order);
+            for path in ["Development/Databases", "Development/Editors", "Games"] {
+                assert!(paths.contains(&path.to_string()), "{} missing from {:?}", path, paths);
+            }
+            assert!(!paths.contains(&"Social".to_string()));
+        }
+        assert_eq!(a.config.category_order, b.config.category_order);
+    }
+
+    #[test]
+    fn never_syncs_machine_specific_fields() {
+        let (dir, shared) = (temp_dir("sync_machines"), temp_dir("sync_shared"));
+        let mut a = Machine::new(&dir, "a");
+        let mut b = Machine::new(&dir, "b");
+        a.config.sync_folder = Some("~/Dropbox/launcher".into());
+        b.config.sync_folder = Some("/Volumes/Team/launcher".into());
+        a.config.wallpaper = Some("/Users/a/.macappcontrol/wallpapers/dunes.jpg".into());
+        a.sync(&shared);
+        let report = b.sync(&shared);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(b.config.sync_folder.as_deref(), Some("/Volumes/Team/launcher"));
+        assert_eq!(b.config.wallpaper, None);
+
+        let content = fs::read_to_string(shared.join(SHARED_FILE)).unwrap();
+        let written: Value = serde_json::from_str(&content).unwrap();
+        for key in ["sync_folder", "wallpaper", "incognito", "log_level", "revision"] {
+            assert!(written.get(key).is_none(), "{} was shared", key);
+        }
+        assert!(written.get("schema_version").is_some());
+    }
+
+    #[test]
+    fn syncs_the_active_config_through_its_folder() {
+        with_test_home(|home| {
+            let shared = home.join("Shared");
+            fs::create_dir_all(&shared).unwrap();
+            update_config(|c| c.sync_folder = Some(shared.to_string_lossy().to_string())).unwrap();
+            let (_, report) = sync_active(None).unwrap().unwrap();
+            assert_eq!(report.status, SyncStatus::Pushed);
+
+            // Another machine adds a script.
+            let shared_file = shared.join(SHARED_FILE);
+            let mut remote = read_config(&shared_file).unwrap().unwrap();
+            remote.scripts.push(script("deploy", "make deploy"));
+            write_shared(&shared_file, &remote).unwrap();
+
+            let (config, report) = sync_active(None).unwrap().unwrap();
+            assert_eq!(report.status, SyncStatus::Pulled);
+            assert_eq!(config.scripts.len(), 1);
+            assert_eq!(config::load_config().scripts.len(), 1);
+            assert_eq!(config.sync_folder.as_deref(), Some(shared.to_string_lossy().as_ref()));
+        });
+    }
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::error::AppError;
+use crate::i18n::{self, Locale};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemAction {
+    LockScreen,
+    Sleep,
+    Restart,
+    ShutDown,
+    LogOut,
+    EmptyTrash,
+    ToggleDarkMode,
+    EjectAllDisks,
+    ShowDesktop,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SystemActionInfo {
+    pub action: SystemAction,
+    pub title: String,
+    pub keywords: Vec<String>,
+    pub requires_confirmation: bool,
+}
+
+/// A program invocation that performs a system action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl SystemCommand {
+    fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    fn osascript(script: &str) -> Self {
+        Self::new("osascript", &["-e", script])
+    }
+}
+
+pub trait ActionExecutor {
+    fn execute(&self, command: &SystemCommand) -> Result<(), AppError>;
+}
+
+pub struct ProcessExecutor;
+
+impl ActionExecutor for ProcessExecutor {
+    fn execute(&self, command: &SystemCommand) -> Result<(), AppError> {
+        let status = Command::new(&command.program)
+            .args(&command.args)
+            .status()
+            .map_err(|e| AppError::command(&command.program, e))?;
+        if !status.success() {
+            return Err(AppError::command(&command.program, status));
+        }
+        Ok(())
+    }
+}
+
+const ALL_ACTIONS: [SystemAction; 9] = [
+    SystemAction::LockScreen,
+    SystemAction::Sleep,
+    SystemAction::Restart,
+    SystemAction::ShutDown,
+    SystemAction::LogOut,
+    SystemAction::EmptyTrash,
+    SystemAction::ToggleDarkMode,
+    SystemAction::EjectAllDisks,
+    SystemAction::ShowDesktop,
+];
+
+impl SystemAction {
+    /// Stable id, as serialized.
+    pub fn id(&self) -> &'static str {
+        match self {
+            SystemAction::LockScreen => "lock_screen",
+            SystemAction::Sleep => "sleep",
+            SystemAction::Restart => "restart",
+            SystemAction::ShutDown => "shut_down",
+            SystemAction::LogOut => "log_out",
+            SystemAction::EmptyTrash => "empty_trash",
+            SystemAction::ToggleDarkMode => "toggle_dark_mode",
+            SystemAction::EjectAllDisks => "eject_all_disks",
+            SystemAction::ShowDesktop => "show_desktop",
+        }
+    }
+
+    pub fn title(&self, locale: Locale) -> &'static str {
+        i18n::tr(locale, &format!("action.{}", self.id()))
+    }
+
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            SystemAction::LockScreen => &["lock"],
+            SystemAction::Sleep => &["suspend"],
+            SystemAction::Restart => &["reboot"],
+            SystemAction::ShutDown => &["power off", "turn off"],
+            SystemAction::LogOut => &["sign out"],
+            SystemAction::EmptyTrash => &["bin", "delete"],
+            SystemAction::ToggleDarkMode => &["appearance", "light mode", "theme"],
+            SystemAction::EjectAllDisks => &["unmount", "volumes"],
+            SystemAction::ShowDesktop => &["hide windows"],
+        }
+    }
+
+    /// Actions that lose unsaved work or data must be confirmed by the user.
+    pub fn requires_confirmation(&self) -> bool {
+        matches!(
+            self,
+            SystemAction::Restart
+                | SystemAction::ShutDown
+                | SystemAction::LogOut
+                | SystemAction::EmptyTrash
+        )
+    }
+
+    pub fn command(&self) -> SystemCommand {
+        match self {
+            SystemAction::LockScreen => SystemCommand::osascript(
+                "tell application \"System Events\" to keystroke \"q\" using {control down, command down}",
+            ),
+            SystemAction::Sleep => SystemCommand::new("pmset", &["sleepnow"]),
+            SystemAction::Restart => {
+                SystemCommand::osascript("tell application \"System Events\" to restart")
+            }
+            SystemAction::ShutDown => {
+                SystemCommand::osascript("tell application \"System Events\" to shut down")
+            }
+            SystemAction::LogOut => {
+                SystemCommand::osascript("tell application \"System Events\" to log out")
+            }
+            SystemAction::EmptyTrash => {
+                SystemCommand::osascript("tell application \"Finder\" to empty trash")
+            }
+            SystemAction::ToggleDarkMode => SystemCommand::osascript(
+                "tell application \"System Events\" to tell appearance preferences to set dark mode to not dark mode",
+            ),
+            SystemAction::EjectAllDisks => SystemCommand::osascript(
+                "tell application \"Finder\" to eject (every disk whose ejectable is true)",
+            ),
+            SystemAction::ShowDesktop => {
+                SystemCommand::new("open", &["-a", "Mission Control", "--args", "1"])
+            }
+        }
+    }
+
+    fn info(&self, locale: Locale) -> SystemActionInfo {
+        SystemActionInfo {
+            action: *self,
+            title: self.title(locale).to_string(),
+            keywords: self.keywords().iter().map(|k| k.to_string()).collect(),
+            requires_confirmation: self.requires_confirmation(),
+        }
+    }
+}
+
+pub fn run_action(
+    executor: &dyn ActionExecutor,
+    action: SystemAction,
+    confirmed: bool,
+) -> Result<(), AppError> {
+    if action.requires_confirmation() && !confirmed {
+        return Err(AppError::ConfirmationRequired {
+            action: action.id().to_string(),
+        });
+    }
+    executor.execute(&action.command())
+}
+
+#[tauri::command]
+pub fn list_system_actions() -> Vec<SystemActionInfo> {
+    let locale = i18n::current();
+    ALL_ACTIONS.iter().map(|action| action.info(locale)).collect()
+}
+
+#[tauri::command]
+pub fn run_system_action(action: SystemAction, confirmed: Option<bool>) -> Result<(), AppError> {
+    run_action(&ProcessExecutor, action, confirmed.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_test_home;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct RecordingExecutor {
+        commands: RefCell<Vec<SystemCommand>>,
+    }
+
+    impl ActionExecutor for RecordingExecutor {
+        fn execute(&self, command: &SystemCommand) -> Result<(), AppError> {
+            self.commands.borrow_mut().push(command.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn maps_actions_to_commands() {
+        let executor = RecordingExecutor::default();
+        run_action(&executor, SystemAction::Sleep, false).unwrap();
+        run_action(&executor, SystemAction::ShowDesktop, false).unwrap();
+        run_action(&executor, SystemAction::EmptyTrash, true).unwrap();
+
+        let commands = executor.commands.borrow();
+        assert_eq!(commands[0], SystemCommand::new("pmset", &["sleepnow"]));
+        assert_eq!(commands[1].program, "open");
+        assert_eq!(
+            commands[2],
+            SystemCommand::osascript("tell application \"Finder\" to empty trash")
+        );
+    }
+
+    #[test]
+    fn ids_match_serialized_names() {
+        for action in ALL_ACTIONS {
+            assert_eq!(serde_json::to_value(action).unwrap(), action.id());
+            assert!(!action.title(Locale::ZhCn).is_empty());
+        }
+    }
+
+    #[test]
+    fn destructive_actions_need_confirmation() {
+        let executor = RecordingExecutor::default();
+        for action in [
+            SystemAction::Restart,
+            SystemAction::ShutDown,
+            SystemAction::LogOut,
+            SystemAction::EmptyTrash,
+        ] {
+            let err = run_action(&executor, action, false).unwrap_err();
+            assert_eq!(err.code(), "confirmation_required");
+        }
+        assert!(executor.commands.borrow().is_empty());
+
+        run_action(&executor, SystemAction::ShutDown, true).unwrap();
+        assert_eq!(executor.commands.borrow().len(), 1);
+    }
+
+    #[test]
+    fn every_action_is_listed_once() {
+        with_test_home(|_| {
+            let listed = list_system_actions();
+            assert_eq!(listed.len(), ALL_ACTIONS.len());
+            for action in ALL_ACTIONS {
+                assert_eq!(listed.iter().filter(|i| i.action == action).count(), 1);
+                assert!(!action.command().program.is_empty());
+            }
+            assert!(!listed
+                .iter()
+                .find(|i| i.action == SystemAction::LockScreen)
+                .unwrap()
+                .requires_confirmation);
+        });
+    }
+}
@@ -0,0 +1,54 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config;
+
+static TEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Runs `f` with `HOME` pointed at a fresh temp dir and an empty config
+/// cache. Tests touching the config dir must go through this so they don't
+/// race on the process-wide environment.
+pub(crate) fn with_test_home<T>(f: impl FnOnce(PathBuf) -> T) -> T {
+    let _g = TEST_LOCK.lock();
+    let home = temp_dir("home");
+    std::env::set_var("HOME", &*home);
+    config::reset_cache();
+    let out = f(home.to_path_buf());
+    config::reset_cache();
+    out
+}
+
+/// A fresh directory under the system temp dir, removed when dropped.
+pub(crate) struct TempDir(PathBuf);
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Creates `macappcontrol_test_<name>_<timestamp>` in the system temp dir.
+pub(crate) fn temp_dir(name: &str) -> TempDir {
+    let dir = std::env::temp_dir().join(format!(
+        "macappcontrol_test_{}_{}",
+        name,
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    TempDir(dir)
+}
@@ -0,0 +1,623 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::categories::{app_name, is_within};
+use crate::config::{get_config_path, load_config, update_config, AppConfig};
+use crate::error::{AppError, IoAction};
+
+const DEFAULT_TOP: usize = 5;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Longest range stats cover when no retention period is set.
+const MAX_RANGE_DAYS: u32 = 3660;
+
+/// Launches per app per day, kept out of `config.json` so the config stays
+/// small and launches don't end up in undo history or sync conflicts.
+/// `config.json` still carries undated lifetime totals in `usage_counts`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+struct UsageLog {
+    /// Days are counted from 1970-01-01 in local time; days without
+    /// launches are not stored.
+    apps: HashMap<String, BTreeMap<i32, u32>>,
+}
+
+static LOG_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn log_path() -> PathBuf {
+    get_config_path().join("usage.json")
+}
+
+fn day_number(date: NaiveDate) -> i32 {
+    (date - NaiveDate::default()).num_days() as i32
+}
+
+fn date_of(day: i32) -> NaiveDate {
+    NaiveDate::default() + Duration::days(i64::from(day))
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Reads the launch log. A file that doesn't parse is moved aside rather
+/// than overwritten by the next launch, so it can still be recovered.
+fn load() -> UsageLog {
+    let path = log_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return UsageLog::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        let backup = crate::config::move_aside(&path);
+        tracing::error!(backup = ?backup, "usage log could not be parsed: {}", e);
+        UsageLog::default()
+    })
+}
+
+fn store(log: &UsageLog) -> Result<(), AppError> {
+    let path = log_path();
+    let tmp_path = path.with_extension("json.tmp");
+    let content = serde_json::to_string(log).map_err(|e| AppError::io(IoAction::Write, &path, e))?;
+    fs::write(&tmp_path, content)
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .map_err(|e| {
+            tracing::error!(path = %path.display(), "could not save usage log: {}", e);
+            AppError::io(IoAction::Write, &path, e)
+        })
+}
+
+fn record_on(path: &str, date: NaiveDate) -> Result<(), AppError> {
+    let _guard = LOG_LOCK.lock();
+    let mut log = load();
+    let days = log.apps.entry(path.to_string()).or_default();
+    *days.entry(day_number(date)).or_insert(0) += 1;
+    store(&log)
+}
+
+/// Counts a launch of `path` towards today.
+pub(crate) fn record_launch(path: &str) -> Result<(), AppError> {
+    record_on(path, today())?;
+    enforce_retention()
+}
+
+/// Keeps only the last `days` days, today included.
+fn prune(log: &mut UsageLog, days: u32, today: NaiveDate) {
+    let oldest = day_number(today) - days as i32 + 1;
+    for launches in log.apps.values_mut() {
+        *launches = launches.split_off(&oldest);
+    }
+    log.apps.retain(|_, launches| !launches.is_empty());
+}
+
+fn enforce_retention_on(today: NaiveDate) -> Result<(), AppError> {
+    let Some(days) = load_config().usage_retention_days else {
+        return Ok(());
+    };
+    let _guard = LOG_LOCK.lock();
+    let mut log = load();
+    let before = log.clone();
+    prune(&mut log, days, today);
+    if log != before {
+        tracing::info!(days, "pruned launches past the retention period");
+        store(&log)?;
+    }
+    update_config(|config| {
+        config.usage_counts.retain(|path, count| {
+            let kept: u32 = log.apps.get(path).map_or(0, |launches| launches.values().sum());
+            *count = (*count).min(kept);
+            *count > 0
+        });
+    })
+}
+
+/// Applies `usage_retention_days`: drops older launches from the daily log
+/// and lowers lifetime counts to what is left, since those carry no dates.
+pub(crate) fn enforce_retention() -> Result<(), AppError> {
+    enforce_retention_on(today())
+}
+
+/// Which launch data `clear_usage_data` removes.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsageScope {
+    All,
+    App { path: String },
+    /// Apps in this category or any below it.
+    Category { category: String },
+}
+
+fn apps_in_category(config: &AppConfig, category: &str) -> HashSet<String> {
+    let in_category = |tags: &[String]| tags.iter().any(|t| is_within(t, category));
+    let mut paths: HashSet<String> = config
+        .categories
+        .iter()
+        .filter(|(_, c)| is_within(c, category))
+        .map(|(path, _)| path.clone())
+        .chain(
+            config
+                .tags
+                .iter()
+                .filter(|(_, tags)| in_category(tags))
+                .map(|(path, _)| path.clone()),
+        )
+        .collect();
+    // Smart and declared-type categories only exist on scanned apps.
+    paths.extend(
+        crate::apps::get_installed_apps(None)
+            .into_iter()
+            .filter(|app| in_category(&app.tags))
+            .map(|app| app.path),
+    );
+    paths
+}
+
+fn clear(scope: &UsageScope) -> Result<(), AppError> {
+    let paths = match scope {
+        UsageScope::All => None,
+        UsageScope::App { path } => Some(HashSet::from([path.clone()])),
+        UsageScope::Category { category } => {
+            let category = crate::categories::normalize(category)?;
+            Some(apps_in_category(&load_config(), &category))
+        }
+    };
+    let cleared = |path: &String| paths.as_ref().is_none_or(|paths| paths.contains(path));
+
+    let _guard = LOG_LOCK.lock();
+    let mut log = load();
+    log.apps.retain(|path, _| !cleared(path));
+    store(&log)?;
+    update_config(|config| config.usage_counts.retain(|path, _| !cleared(path)))?;
+    // Undo snapshots carry launch counts and could bring them back.
+    crate::history::clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_usage_data(scope: UsageScope) -> Result<(), AppError> {
+    clear(&scope).inspect_err(|e| tracing::warn!(scope = ?scope, "could not clear usage data: {}", e))
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+}
+
+impl GroupBy {
+    fn period_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start + Duration::days(1),
+            Self::Week => start + Duration::days(7),
+            Self::Month => start + chrono::Months::new(1),
+        }
+    }
+}
+
+/// Inclusive date range, as "YYYY-MM-DD". `to` defaults to today. Ranges
+/// longer than the retention period are cut to its last days, since nothing
+/// older is kept.
+#[derive(Deserialize, Debug, Clone)]
+pub struct UsageRange {
+    pub from: String,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+impl UsageRange {
+    fn dates(&self) -> Result<(NaiveDate, NaiveDate), AppError> {
+        let parse = |s: &str| {
+            NaiveDate::parse_from_str(s.trim(), DATE_FORMAT).map_err(|_| AppError::InvalidDate { value: s.to_string() })
+        };
+        let from = parse(&self.from)?;
+        let to = self.to.as_deref().map(parse).transpose()?.unwrap_or_else(today);
+        if from > to {
+            return Err(AppError::InvalidRange {
+                from: from.format(DATE_FORMAT).to_string(),
+                to: to.format(DATE_FORMAT).to_string(),
+            });
+        }
+        let days = load_config().usage_retention_days.map_or(MAX_RANGE_DAYS, |d| d.clamp(1, MAX_RANGE_DAYS));
+        Ok((from.max(to - Duration::days(i64::from(days) - 1)), to))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ItemUsage {
+    pub path: String,
+    pub name: String,
+    pub launches: u32,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AppUsage {
+    pub path: String,
+    pub name: String,
+    pub launches: u32,
+    /// Launches in the last period minus those in the one before it.
+    pub trend: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeriodUsage {
+    pub start: String,
+    pub launches: u32,
+    /// Launches minus those in the previous period.
+    pub change: i64,
+    /// The most launched apps in this period.
+    pub top: Vec<ItemUsage>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct UsageStats {
+    pub from: String,
+    pub to: String,
+    pub launches: u32,
+    /// Every app launched in the range, most launched first.
+    pub apps: Vec<AppUsage>,
+    /// Every period in the range, oldest first, including empty ones.
+    pub periods: Vec<PeriodUsage>,
+}
+
+/// Launches per app in each period overlapping `from..=to`.
+fn period_counts(
+    log: &UsageLog,
+    from: NaiveDate,
+    to: NaiveDate,
+    group_by: GroupBy,
+) -> Vec<(NaiveDate, HashMap<String, u32>)> {
+    let mut periods: BTreeMap<NaiveDate, HashMap<String, u32>> = BTreeMap::new();
+    let mut start = group_by.period_start(from);
+    while start <= to {
+        periods.insert(start, HashMap::new());
+        start = group_by.next(start);
+    }
+    for (path, days) in &log.apps {
+        for (&day, &count) in days.range(day_number(from)..=day_number(to)) {
+            let period = periods.entry(group_by.period_start(date_of(day))).or_default();
+            *period.entry(path.clone()).or_insert(0) += count;
+        }
+    }
+    periods.into_iter().collect()
+}
+
+/// Most launched first, ties by path so results are stable.
+fn ranked(counts: &HashMap<String, u32>) -> Vec<(&String, u32)> {
+    let mut ranked: Vec<(&String, u32)> = counts
+        .iter()
+        .filter(|(_, &count)| count > 0)
+        .map(|(path, &count)| (path, count))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    ranked
+}
+
+fn stats(log: &UsageLog, range: &UsageRange, group_by: GroupBy, top: usize) -> Result<UsageStats, AppError> {
+    let (from, to) = range.dates()?;
+    let periods = period_counts(log, from, to, group_by);
+
+    let mut totals: HashMap<String, u32> = HashMap::new();
+    for (_, counts) in &periods {
+        for (path, count) in counts {
+            *totals.entry(path.clone()).or_insert(0) += count;
+        }
+    }
+    let period_count = |index: Option<usize>, path: &str| -> i64 {
+        index
+            .and_then(|i| periods.get(i))
+            .and_then(|(_, counts)| counts.get(path))
+            .map_or(0, |&c| i64::from(c))
+    };
+    let last = periods.len().checked_sub(1);
+    let apps = ranked(&totals)
+        .into_iter()
+        .map(|(path, launches)| AppUsage {
+            path: path.clone(),
+            name: app_name(path),
+            launches,
+            trend: period_count(last, path) - period_count(last.and_then(|i| i.checked_sub(1)), path),
+        })
+        .collect();
+
+    let mut previous = 0i64;
+    let periods = periods
+        .iter()
+        .map(|(start, counts)| {
+            let launches: u32 = counts.values().sum();
+            let change = i64::from(launches) - previous;
+            previous = i64::from(launches);
+            PeriodUsage {
+                start: start.format(DATE_FORMAT).to_string(),
+                launches,
+                change,
+                top: ranked(counts)
+                    .into_iter()
+                    .take(top)
+                    .map(|(path, launches)| ItemUsage {
+                        path: path.clone(),
+                        name: app_name(path),
+                        launches,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(UsageStats {
+        from: from.format(DATE_FORMAT).to_string(),
+        to: to.format(DATE_FORMAT).to_string(),
+        launches: totals.values().sum(),
+        apps,
+        periods,
+    })
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per app per period with launches, oldest period first.
+fn to_csv(log: &UsageLog, range: &UsageRange, group_by: GroupBy) -> Result<String, AppError> {
+    let (from, to) = range.dates()?;
+    let mut csv = String::from("period,path,name,launches\n");
+    for (start, counts) in period_counts(log, from, to, group_by) {
+        for (path, launches) in ranked(&counts) {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                start.format(DATE_FORMAT),
+                csv_field(path),
+                csv_field(&app_name(path)),
+                launches
+            ));
+        }
+    }
+    Ok(csv)
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[tauri::command]
+pub fn get_usage_stats(range: UsageRange, group_by: GroupBy, top: Option<usize>) -> Result<UsageStats, AppError> {
+    let _guard = LOG_LOCK.lock();
+    stats(&load(), &range, group_by, top.unwrap_or(DEFAULT_TOP))
+}
+
+/// Writes usage for `range` to `path`. JSON exports carry the full stats,
+/// with every app in each period's list.
+#[tauri::command]
+pub fn export_usage_stats(
+    range: UsageRange,
+    group_by: GroupBy,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), AppError> {
+    let log = {
+        let _guard = LOG_LOCK.lock();
+        load()
+    };
+    let content = match format {
+        ExportFormat::Csv => to_csv(&log, &range, group_by)?,
+        ExportFormat::Json => serde_json::to_string_pretty(&stats(&log, &range, group_by, usize::MAX)?)
+            .map_err(|e| AppError::io(IoAction::Write, &path, e))?,
+    };
+    fs::write(Path::new(&path), content).map_err(|e| AppError::io(IoAction::Write, &path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_test_home;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    fn range(from: &str, to: &str) -> UsageRange {
+        UsageRange {
+            from: from.to_string(),
+            to: Some(to.to_string()),
+        }
+    }
+
+    fn launch(path: &str, day: &str, times: u32) {
+        for _ in 0..times {
+            record_on(path, date(day)).unwrap();
+        }
+    }
+
+    #[test]
+    fn stores_one_count_per_app_and_day() {
+        with_test_home(|_| {
+            launch("/Applications/Xcode.app", "2026-03-02", 2);
+            launch("/Applications/Xcode.app", "2026-03-03", 1);
+            let log = load();
+            let days = &log.apps["/Applications/Xcode.app"];
+            assert_eq!(days.len(), 2);
+            assert_eq!(days[&day_number(date("2026-03-02"))], 2);
+            assert_eq!(date_of(day_number(date("2026-03-03"))), date("2026-03-03"));
+        });
+    }
+
+    #[test]
+    fn moves_a_corrupt_log_aside() {
+        with_test_home(|_| {
+            fs::write(log_path(), "{\"apps\": {\"/Xcode.app\": ").unwrap();
+            launch("/Applications/Slack.app", "2026-03-02", 1);
+            assert_eq!(load().apps.len(), 1);
+            let moved: Vec<_> = fs::read_dir(get_config_path())
+                .unwrap()
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with("usage.corrupt-"))
+                .collect();
+            assert_eq!(moved.len(), 1);
+        });
+    }
+
+    #[test]
+    fn groups_by_week_with_totals_trends_and_top() {
+        with_test_home(|_| {
+            // 2026-03-02 is a Monday.
+            launch("/Applications/Xcode.app", "2026-03-02", 3);
+            launch("/Applications/Slack.app", "2026-03-04", 1);
+            launch("/Applications/Slack.app", "2026-03-10", 4);
+            launch("/Applications/Figma.app", "2026-03-11", 2);
+            launch("/Applications/Figma.app", "2026-03-30", 9);
+
+            let stats = get_usage_stats(range("2026-03-01", "2026-03-15"), GroupBy::Week, Some(1)).unwrap();
+            assert_eq!(stats.launches, 10);
+            let starts: Vec<&str> = stats.periods.iter().map(|p| p.start.as_str()).collect();
+            assert_eq!(starts, vec!["2026-02-23", "2026-03-02", "2026-03-09"]);
+            assert_eq!(stats.periods[0].launches, 0);
+            assert_eq!(stats.periods[1].launches, 4);
+            assert_eq!(stats.periods[2].launches, 6);
+            assert_eq!(stats.periods[2].change, 2);
+            assert_eq!(stats.periods[2].top.len(), 1);
+            assert_eq!(stats.periods[2].top[0].name, "Slack");
+
+            let apps: Vec<(&str, u32, i64)> =
+                stats.apps.iter().map(|a| (a.name.as_str(), a.launches, a.trend)).collect();
+            assert_eq!(apps, vec![("Slack", 5, 3), ("Xcode", 3, -3), ("Figma", 2, 2)]);
+
+            let months = get_usage_stats(range("2026-03-01", "2026-04-30"), GroupBy::Month, None).unwrap();
+            let totals: Vec<u32> = months.periods.iter().map(|p| p.launches).collect();
+            assert_eq!(totals, vec![19, 0]);
+            assert!(get_usage_stats(range("2026-03-02", "2026-03-01"), GroupBy::Day, None).is_err());
+        });
+    }
+
+    #[test]
+    fn cuts_ranges_to_the_retention_period() {
+        with_test_home(|_| {
+            let days = get_usage_stats(range("0001-01-01", "2026-03-15"), GroupBy::Day, None).unwrap();
+            assert_eq!(days.periods.len(), MAX_RANGE_DAYS as usize);
+
+            update_config(|c| c.usage_retention_days = Some(30)).unwrap();
+            let days = get_usage_stats(range("2026-01-01", "2026-03-15"), GroupBy::Day, None).unwrap();
+            assert_eq!(days.periods.len(), 30);
+            assert_eq!(days.periods[0].start, "2026-02-14");
+        });
+    }
+
+    #[test]
+    fn retention_prunes_old_launches_and_lifetime_counts() {
+        with_test_home(|_| {
+            launch("/Applications/Xcode.app", "2026-02-01", 2);
+            launch("/Applications/Xcode.app", "2026-03-09", 1);
+            launch("/Applications/Slack.app", "2026-02-01", 4);
+            update_config(|c| {
+                c.usage_counts.insert("/Applications/Xcode.app".into(), 40);
+                c.usage_counts.insert("/Applications/Slack.app".into(), 4);
+            })
+            .unwrap();
+
+            // Nothing is pruned until a retention period is set.
+            enforce_retention_on(date("2026-03-10")).unwrap();
+            assert_eq!(load().apps.len(), 2);
+
+            update_config(|c| c.usage_retention_days = Some(2)).unwrap();
+            enforce_retention_on(date("2026-03-10")).unwrap();
+            let log = load();
+            assert_eq!(log.apps.len(), 1);
+            assert_eq!(log.apps["/Applications/Xcode.app"].len(), 1);
+            let counts = load_config().usage_counts;
+            assert_eq!(counts.get("/Applications/Xcode.app"), Some(&1));
+            assert!(!counts.contains_key("/Applications/Slack.app"));
+
+            enforce_retention_on(date("2026-03-11")).unwrap();
+            assert!(load().apps.is_empty());
+        });
+    }
+
+    #[test]
+    fn clears_usage_by_app_category_and_all() {
+        with_test_home(|_| {
+            for path in ["/Xcode.app", "/Rider.app", "/Slack.app"] {
+                launch(path, "2026-03-02", 1);
+                update_config(|c| {
+                    c.usage_counts.insert(path.into(), 1);
+                })
+                .unwrap();
+            }
+            crate::config::update_app_category("/Xcode.app".into(), "Development/IDEs".into()).unwrap();
+            crate::config::update_app_category("/Rider.app".into(), "Development".into()).unwrap();
+            assert!(crate::history::state().undo.is_some());
+
+            clear_usage_data(UsageScope::Category {
+                category: "Development".into(),
+            })
+            .unwrap();
+            assert_eq!(load().apps.keys().collect::<Vec<_>>(), vec!["/Slack.app"]);
+            assert_eq!(load_config().usage_counts.len(), 1);
+            assert!(crate::history::state().undo.is_none());
+
+            launch("/Xcode.app", "2026-03-03", 1);
+            clear_usage_data(UsageScope::App {
+                path: "/Slack.app".into(),
+            })
+            .unwrap();
+            assert_eq!(load().apps.keys().collect::<Vec<_>>(), vec!["/Xcode.app"]);
+            assert!(load_config().usage_counts.is_empty());
+
+            clear_usage_data(UsageScope::All).unwrap();
+            assert!(load().apps.is_empty());
+            assert!(clear_usage_data(UsageScope::Category { category: " ".into() }).is_err());
+        });
+    }
+
+    #[test]
+    fn exports_csv_and_json() {
+        with_test_home(|home| {
+            launch("/Applications/Visual Studio Code, Insiders.app", "2026-03-02", 2);
+            launch("/Applications/Slack.app", "2026-03-02", 1);
+            let csv_path = home.join("usage.csv");
+            export_usage_stats(
+                range("2026-03-02", "2026-03-02"),
+                GroupBy::Day,
+                ExportFormat::Csv,
+                csv_path.to_string_lossy().to_string(),
+            )
+            .unwrap();
+            assert_eq!(
+                fs::read_to_string(&csv_path).unwrap(),
+                "period,path,name,launches\n\
+                 2026-03-02,\"/Applications/Visual Studio Code, Insiders.app\",\"Visual Studio Code, Insiders\",2\n\
+                 2026-03-02,/Applications/Slack.app,Slack,1\n"
+            );
+
+            let json_path = home.join("usage.json.export");
+            export_usage_stats(
+                range("2026-03-02", "2026-03-02"),
+                GroupBy::Day,
+                ExportFormat::Json,
+                json_path.to_string_lossy().to_string(),
+            )
+            .unwrap();
+            let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+            assert_eq!(json["launches"], 3);
+            assert_eq!(json["periods"][0]["top"].as_array().unwrap().len(), 2);
+        });
+    }
+}
@@ -1,4 +1,5 @@
 use crate::config;
+use crate::error::{AppError, IoAction, Resource};
 use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -12,13 +13,24 @@ pub struct WallpaperFile {
     pub modified_ms: Option<u128>,
 }
 
-fn wallpapers_dir() -> PathBuf {
+pub(crate) fn wallpapers_dir() -> PathBuf {
     let mut dir = config::get_config_path();
     dir.push("wallpapers");
     let _ = fs::create_dir_all(&dir);
     dir
 }
 
+/// The local file a wallpaper setting points at; `None` for empty settings
+/// and remote URLs.
+pub(crate) fn local_wallpaper_path(wallpaper: &str) -> Option<PathBuf> {
+    let raw = wallpaper.trim();
+    if raw.is_empty() || raw.starts_with("http://") || raw.starts_with("https://") {
+        return None;
+    }
+    let raw = raw.trim_start_matches("file://").replace("%20", " ");
+    Some(PathBuf::from(raw))
+}
+
 fn is_supported_image_ext(ext: &str) -> bool {
     matches!(
         ext.to_ascii_lowercase().as_str(),
@@ -88,7 +100,7 @@ pub fn list_wallpapers() -> Vec<WallpaperFile> {
 }
 
 #[tauri::command]
-pub fn import_wallpaper(source_path: String) -> Result<String, String> {
+pub fn import_wallpaper(source_path: String) -> Result<String, AppError> {
     let mut raw = source_path.trim().to_string();
     if raw.starts_with("file://") {
         raw = raw.trim_start_matches("file://").to_string();
@@ -96,7 +108,7 @@ pub fn import_wallpaper(source_path: String) -> Result<String, String> {
     let raw = raw.replace("%20", " ");
     let src = PathBuf::from(raw);
     if !src.is_file() {
-        return Err("源文件不存在或不是文件（请确认选择的是本地图片文件）".to_string());
+        return Err(AppError::not_found(Resource::File, src.display().to_string()));
     }
 
     let ext = src
@@ -105,11 +117,12 @@ pub fn import_wallpaper(source_path: String) -> Result<String, String> {
         .unwrap_or("")
         .to_ascii_lowercase();
     if !is_supported_image_ext(&ext) {
-        return Err("不支持的图片格式（支持 PNG/JPG/JPEG/WebP/GIF）".to_string());
+        return Err(AppError::UnsupportedFormat {
+            path: src.display().to_string(),
+        });
     }
 
-    let meta = fs::metadata(&src)
-        .map_err(|e| format!("读取源文件信息失败：{e}"))?;
+    let meta = fs::metadata(&src).map_err(|e| AppError::io(IoAction::Read, &src, e))?;
     let modified_ms = file_modified_ms(&meta).unwrap_or(0);
     let size = meta.len();
     let ts = SystemTime::now()
@@ -130,60 +143,43 @@ pub fn import_wallpaper(source_path: String) -> Result<String, String> {
     let mut dest = wallpapers_dir();
     dest.push(filename);
 
-    fs::copy(&src, &dest).map_err(|e| format!("复制图片失败：{e}"))?;
+    fs::copy(&src, &dest).map_err(|e| AppError::io(IoAction::Copy, &src, e))?;
 
     let dest_str = dest.to_string_lossy().to_string();
     if !dest.exists() {
-        return Err("复制完成但目标文件不存在（异常）".to_string());
+        return Err(AppError::not_found(Resource::Wallpaper, dest_str));
     }
     Ok(dest_str)
 }
 
-fn ensure_path_under_dir(target: &Path, dir: &Path) -> Result<(), String> {
+fn ensure_path_under_dir(target: &Path, dir: &Path) -> Result<(), AppError> {
     let dir = dir
         .canonicalize()
-        .map_err(|_| "壁纸目录不可用".to_string())?;
+        .map_err(|e| AppError::io(IoAction::Read, dir, e))?;
     let target = target
         .canonicalize()
-        .map_err(|_| "目标文件不可用".to_string())?;
+        .map_err(|_| AppError::not_found(Resource::Wallpaper, target.display().to_string()))?;
     if !target.starts_with(&dir) {
-        return Err("只能删除应用保存的壁纸".to_string());
+        return Err(AppError::NotManaged {
+            path: target.display().to_string(),
+        });
     }
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_wallpaper(path: String) -> Result<(), String> {
+pub fn delete_wallpaper(path: String) -> Result<(), AppError> {
     let dir = wallpapers_dir();
     let target = PathBuf::from(path.trim());
     ensure_path_under_dir(&target, &dir)?;
-    fs::remove_file(&target).map_err(|_| "删除失败".to_string())?;
+    fs::remove_file(&target).map_err(|e| AppError::io(IoAction::Delete, &target, e))?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use once_cell::sync::Lazy;
-    use parking_lot::Mutex;
-
-    static TEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
-
-    fn with_test_home<T>(f: impl FnOnce(PathBuf) -> T) -> T {
-        let _g = TEST_LOCK.lock();
-        let home = std::env::temp_dir().join(format!(
-            "macappcontrol_test_home_{}",
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis()
-        ));
-        fs::create_dir_all(&home).unwrap();
-        std::env::set_var("HOME", &home);
-        let out = f(home.clone());
-        let _ = fs::remove_dir_all(&home);
-        out
-    }
+    use crate::test_support::with_test_home;
 
     #[test]
     fn import_list_delete_roundtrip() {
@@ -208,7 +204,7 @@ mod tests {
             let outside = home.join("outside.png");
             fs::write(&outside, b"outside").unwrap();
             let err = delete_wallpaper(outside.to_string_lossy().to_string()).unwrap_err();
-            assert!(err.contains("只能删除应用保存的壁纸"));
+            assert_eq!(err.code(), "not_managed");
         });
     }
 }
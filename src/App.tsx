@@ -12,8 +12,10 @@ import useFilteredApps from "./hooks/useFilteredApps";
 import useKeyboardNavigation from "./hooks/useKeyboardNavigation";
 import { buildAppContextMenuItems } from "./lib/contextMenuItems";
 import { resolveWallpaperUrl } from "./lib/wallpaper";
+import { appInCategory, flattenCategoryTree } from "./lib/categories";
 import { useAppStore } from "./store/useAppStore";
 import { revealInFinder } from "./api/tauri";
+import type { AppConfig, ConfigLoadError } from "./types/app";
 
 function App() {
   const {
@@ -24,9 +26,14 @@ function App() {
     appsRefreshing,
     notice,
     setNotice,
+    reportConfigLoadError,
+    applyExternalConfig,
     launchApp,
     setAppCategory,
-    reorderCategories
+    toggleAppTag,
+    reorderCategories,
+    undo,
+    redo
   } = useAppStore();
 
   const [searchQuery, setSearchQuery] = useState("");
@@ -62,7 +69,7 @@ function App() {
   const allCategories = useMemo(() => {
     if (!config) return defaultCategories;
     return config.category_order && config.category_order.length > 0
-      ? config.category_order
+      ? flattenCategoryTree(config.category_order)
       : [...defaultCategories, ...(config.user_categories || []).filter(c => !defaultCategories.includes(c))];
   }, [config, defaultCategories]);
 
@@ -87,8 +94,18 @@ function App() {
       loadApps(false);
     });
 
+    const unlistenConfigError = listen<ConfigLoadError>('config-load-error', (event) => {
+      reportConfigLoadError(event.payload);
+    });
+
+    const unlistenConfigChanged = listen<AppConfig>('config-changed', (event) => {
+      applyExternalConfig(event.payload);
+    });
+
     return () => {
       unlistenFocus.then(f => f());
+      unlistenConfigError.then(f => f());
+      unlistenConfigChanged.then(f => f());
     }
   }, []);
 
@@ -134,13 +151,15 @@ function App() {
     searchQuery,
     setSearchQuery,
     onLaunch: launchApp,
+    onUndo: undo,
+    onRedo: redo,
   });
 
   const getCategoryCount = (category: string) => {
     // Simplified: in a real refactor we'd move this to store or a memo
     if (category === "Frequent") return apps.filter(a => (a.usage_count || 0) > 0).length;
     if (category === "Scripts") return apps.filter(a => a.is_script).length;
-    return apps.filter(a => a.category === category).length;
+    return apps.filter(a => appInCategory(a, category)).length;
   };
 
   return (
@@ -231,6 +250,7 @@ function App() {
           allCategories,
           onLaunch: launchApp,
           onSetCategory: setAppCategory,
+          onToggleTag: toggleAppTag,
           onRevealInFinder: revealInFinder,
           onCopyText: (text) => navigator.clipboard.writeText(text),
         })}
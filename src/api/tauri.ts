@@ -1,12 +1,94 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { AppConfig, AppInfo, WallpaperFile } from "../types/app";
+import type {
+  AppConfig,
+  AppInfo,
+  ConfigBackup,
+  ConfigLoadError,
+  DiagnosticsReport,
+  FileResult,
+  HistoryState,
+  LogEntry,
+  LogLevel,
+  PluginManifest,
+  PluginResult,
+  ProfileInfo,
+  SmartCategory,
+  SyncReport,
+  SystemAction,
+  SystemActionInfo,
+  UsageGroupBy,
+  UsageRange,
+  UsageScope,
+  UsageStats,
+  WallpaperFile,
+} from "../types/app";
+
+export function patchConfig(patch: Partial<AppConfig>) {
+  return invoke<AppConfig>("patch_config", { patch });
+}
+
+export function undo() {
+  return invoke<string>("undo");
+}
+
+export function redo() {
+  return invoke<string>("redo");
+}
+
+export function getHistoryState() {
+  return invoke<HistoryState>("get_history_state");
+}
 
 export function getConfig() {
   return invoke<AppConfig>("get_config");
 }
 
+export function takeConfigLoadError() {
+  return invoke<ConfigLoadError | null>("take_config_load_error");
+}
+
 export function saveConfig(config: AppConfig) {
-  return invoke<void>("save_config_command", { config });
+  return invoke<AppConfig>("save_config_command", { config });
+}
+
+export function listConfigBackups() {
+  return invoke<ConfigBackup[]>("list_config_backups");
+}
+
+export function restoreConfigBackup(id: string) {
+  return invoke<AppConfig>("restore_config_backup", { id });
+}
+
+export function exportProfile(path: string) {
+  return invoke<void>("export_profile", { path });
+}
+
+export function importProfile(path: string, mode: "replace" | "merge") {
+  return invoke<AppConfig>("import_profile", { path, mode });
+}
+
+export function listProfiles() {
+  return invoke<ProfileInfo[]>("list_profiles");
+}
+
+export function createProfile(name: string, copyCurrent: boolean = false) {
+  return invoke<void>("create_profile", { name, copyCurrent });
+}
+
+export function switchProfile(name: string) {
+  return invoke<AppConfig>("switch_profile", { name });
+}
+
+export function deleteProfile(name: string) {
+  return invoke<void>("delete_profile", { name });
+}
+
+export function setSyncFolder(path: string | null) {
+  return invoke<void>("set_sync_folder", { path });
+}
+
+export function syncConfig(prefer?: "local" | "remote") {
+  return invoke<SyncReport | null>("sync_config", { prefer });
 }
 
 export function getInstalledApps(refresh: boolean = false) {
@@ -33,6 +115,54 @@ export function removeCategory(category: string) {
   return invoke<void>("remove_category", { category });
 }
 
+export function addAppTag(path: string, tag: string) {
+  return invoke<void>("add_app_tag", { path, tag });
+}
+
+export function removeAppTag(path: string, tag: string) {
+  return invoke<void>("remove_app_tag", { path, tag });
+}
+
+export function renameCategory(from: string, to: string) {
+  return invoke<void>("rename_category", { from, to });
+}
+
+export function mergeCategories(from: string, into: string) {
+  return invoke<void>("merge_categories", { from, into });
+}
+
+export function getSmartCategories() {
+  return invoke<SmartCategory[]>("get_smart_categories");
+}
+
+export function setSmartCategories(categories: SmartCategory[]) {
+  return invoke<void>("set_smart_categories", { categories });
+}
+
+export function getUsageStats(range: UsageRange, groupBy: UsageGroupBy, top?: number) {
+  return invoke<UsageStats>("get_usage_stats", { range, groupBy, top });
+}
+
+export function exportUsageStats(range: UsageRange, groupBy: UsageGroupBy, format: "csv" | "json", path: string) {
+  return invoke<void>("export_usage_stats", { range, groupBy, format, path });
+}
+
+export function clearUsageData(scope: UsageScope) {
+  return invoke<void>("clear_usage_data", { scope });
+}
+
+export function getRecentLogs(level?: LogLevel, limit?: number) {
+  return invoke<LogEntry[]>("get_recent_logs", { level, limit });
+}
+
+export function runDiagnostics() {
+  return invoke<DiagnosticsReport>("run_diagnostics");
+}
+
+export function exportSupportBundle(path: string) {
+  return invoke<DiagnosticsReport>("export_support_bundle", { path });
+}
+
 export function updateShortcut(shortcut: string) {
   return invoke<void>("update_shortcut", { shortcut });
 }
@@ -72,3 +202,39 @@ export function importWallpaper(sourcePath: string) {
 export function deleteWallpaper(path: string) {
   return invoke<void>("delete_wallpaper", { path });
 }
+
+export function listPlugins() {
+  return invoke<PluginManifest[]>("list_plugins");
+}
+
+export function searchPlugins(query: string) {
+  return invoke<PluginResult[]>("search_plugins", { query });
+}
+
+export function activatePluginResult(pluginId: string, resultId: string) {
+  return invoke<void>("activate_plugin_result", { pluginId, resultId });
+}
+
+export function searchFiles(query: string, limit?: number) {
+  return invoke<FileResult[]>("search_files", { query, limit });
+}
+
+export function updateFileSearchSettings(roots: string[], ignore: string[]) {
+  return invoke<void>("update_file_search_settings", { roots, ignore });
+}
+
+export function openFile(path: string) {
+  return invoke<void>("open_file", { path });
+}
+
+export function copyPath(path: string) {
+  return invoke<void>("copy_path", { path });
+}
+
+export function listSystemActions() {
+  return invoke<SystemActionInfo[]>("list_system_actions");
+}
+
+export function runSystemAction(action: SystemAction, confirmed: boolean = false) {
+  return invoke<void>("run_system_action", { action, confirmed });
+}
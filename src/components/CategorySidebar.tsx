@@ -1,4 +1,5 @@
 import { Reorder } from "framer-motion";
+import { categoryDepth, categoryLabel } from "../lib/categories";
 
 export default function CategorySidebar({
   categories,
@@ -38,9 +39,9 @@ export default function CategorySidebar({
               onClick={() => onSelectCategory(category, index)}
               onContextMenu={(e) => e.preventDefault()}
             >
-              <div className="category-header">
+              <div className="category-header" style={{ paddingLeft: categoryDepth(category) * 14 }}>
                 <span className="category-icon">{categoryIcons[category] || "📁"}</span>
-                <span className="category-name">{category}</span>
+                <span className="category-name">{categoryLabel(category)}</span>
               </div>
               <span className="category-count">{getCategoryCount(category)}</span>
             </div>
@@ -2,6 +2,8 @@ import AppearanceSection from "./settings/AppearanceSection";
 import GeneralSection from "./settings/GeneralSection";
 import OrganizationSection from "./settings/OrganizationSection";
 import AutomationSection from "./settings/AutomationSection";
+import LogsSection from "./settings/LogsSection";
+import DiagnosticsSection from "./settings/DiagnosticsSection";
 
 export default function SettingsDashboard() {
   return (
@@ -13,6 +15,8 @@ export default function SettingsDashboard() {
         <GeneralSection />
         <OrganizationSection />
         <AutomationSection />
+        <LogsSection />
+        <DiagnosticsSection />
       </div>
     </div>
   );
@@ -7,6 +7,7 @@ import {
     listWallpapers,
     revealInFinder
 } from "../../api/tauri";
+import { errorMessage } from "../../lib/errors";
 import { resolveWallpaperUrl } from "../../lib/wallpaper";
 import type { WallpaperFile } from "../../types/app";
 
@@ -26,13 +27,6 @@ export default function AppearanceSection() {
     const [wallpaperError, setWallpaperError] = useState<string | null>(null);
     const [wallpaperBusy, setWallpaperBusy] = useState(false);
 
-    const toErrorMessage = (e: unknown) => {
-        if (typeof e === "string") return e;
-        const anyErr = e as any;
-        if (anyErr?.message) return String(anyErr.message);
-        return "未知错误";
-    };
-
     const loadSavedWallpapers = async () => {
         setWallpaperLoading(true);
         setWallpaperError(null);
@@ -113,7 +107,7 @@ export default function AppearanceSection() {
                                     updateWallpaper(savedPath);
                                     await loadSavedWallpapers();
                                 } catch (e) {
-                                    setWallpaperError(toErrorMessage(e));
+                                    setWallpaperError(errorMessage(e));
                                 } finally {
                                     setWallpaperBusy(false);
                                 }
@@ -127,8 +121,12 @@ export default function AppearanceSection() {
                         <button
                             className="small-btn"
                             onClick={async () => {
-                                const dir = await getWallpapersDir();
-                                await revealInFinder(dir);
+                                try {
+                                    const dir = await getWallpapersDir();
+                                    await revealInFinder(dir);
+                                } catch (e) {
+                                    setWallpaperError(errorMessage(e));
+                                }
                             }}
                         >
                             打开壁纸文件夹
@@ -0,0 +1,69 @@
+import { useState } from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { exportSupportBundle, runDiagnostics } from "../../api/tauri";
+import { errorMessage } from "../../lib/errors";
+import type { CheckStatus, DiagnosticsReport } from "../../types/app";
+
+const STATUS_ICONS: Record<CheckStatus, string> = { ok: "✓", warning: "!", error: "✕" };
+
+export default function DiagnosticsSection() {
+    const [report, setReport] = useState<DiagnosticsReport | null>(null);
+    const [busy, setBusy] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const run = async (action: () => Promise<DiagnosticsReport | null>) => {
+        setBusy(true);
+        setError(null);
+        try {
+            const result = await action();
+            if (result) setReport(result);
+        } catch (e) {
+            setError(errorMessage(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const exportBundle = async () => {
+        const path = await save({
+            defaultPath: "macappcontrol-support.zip",
+            filters: [{ name: "Zip", extensions: ["zip"] }],
+        });
+        return path ? exportSupportBundle(path) : null;
+    };
+
+    return (
+        <section className="settings-group">
+            <h3 className="group-title">Diagnostics</h3>
+            <div className="group-card">
+                <div className="setting-item">
+                    <div className="setting-label">
+                        <span>Health Check</span>
+                        <small>Spotlight, icons, config, shortcut and wallpaper</small>
+                    </div>
+                    <div className="setting-control">
+                        <button className="small-btn" disabled={busy} onClick={() => run(runDiagnostics)}>
+                            Run
+                        </button>
+                        <button className="small-btn" disabled={busy} onClick={() => run(exportBundle)}>
+                            Export Support Bundle
+                        </button>
+                    </div>
+                </div>
+                {error && (
+                    <div className="setting-item">
+                        <small style={{ color: "var(--danger, #f87171)" }}>{error}</small>
+                    </div>
+                )}
+                {report?.checks.map((check) => (
+                    <div className="setting-item" key={check.id}>
+                        <div className="setting-label">
+                            <span>{STATUS_ICONS[check.status]} {check.title}</span>
+                            <small>{check.detail}</small>
+                        </div>
+                    </div>
+                ))}
+            </div>
+        </section>
+    );
+}
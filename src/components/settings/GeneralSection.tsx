@@ -1,7 +1,7 @@
 import { useAppStore } from "../../store/useAppStore";
 
 export default function GeneralSection() {
-    const { config, updateShortcut } = useAppStore();
+    const { config, updateShortcut, updateLocale, updateIncognito, updateUsageRetention, clearUsageData } = useAppStore();
 
     const handleRecordShortcut = (e: React.KeyboardEvent) => {
         e.preventDefault();
@@ -54,6 +54,56 @@ export default function GeneralSection() {
                         />
                     </div>
                 </div>
+                <div className="setting-item">
+                    <div className="setting-label">
+                        <span>Language</span>
+                        <small>Tray menu and error messages</small>
+                    </div>
+                    <div className="setting-control">
+                        <select
+                            value={config.locale ?? "system"}
+                            onChange={(e) => updateLocale(e.target.value as "system" | "en" | "zh-CN")}
+                        >
+                            <option value="system">System</option>
+                            <option value="en">English</option>
+                            <option value="zh-CN">简体中文</option>
+                        </select>
+                    </div>
+                </div>
+                <div className="setting-item">
+                    <div className="setting-label">
+                        <span>Incognito</span>
+                        <small>Don't record app launches</small>
+                    </div>
+                    <div className="setting-control">
+                        <input
+                            type="checkbox"
+                            checked={!!config.incognito}
+                            onChange={(e) => updateIncognito(e.target.checked)}
+                        />
+                    </div>
+                </div>
+                <div className="setting-item">
+                    <div className="setting-label">
+                        <span>Keep Usage History</span>
+                        <small>Older launches are deleted</small>
+                    </div>
+                    <div className="setting-control">
+                        <select
+                            value={config.usage_retention_days ?? ""}
+                            onChange={(e) => updateUsageRetention(e.target.value ? Number(e.target.value) : null)}
+                        >
+                            <option value="">Forever</option>
+                            <option value="7">7 days</option>
+                            <option value="30">30 days</option>
+                            <option value="90">90 days</option>
+                            <option value="365">1 year</option>
+                        </select>
+                        <button className="small-btn" onClick={() => clearUsageData({ kind: "all" })}>
+                            Clear
+                        </button>
+                    </div>
+                </div>
             </div>
         </section>
     );
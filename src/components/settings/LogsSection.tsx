@@ -0,0 +1,78 @@
+import { useEffect, useState } from "react";
+import { useAppStore } from "../../store/useAppStore";
+import { getRecentLogs } from "../../api/tauri";
+import { errorMessage } from "../../lib/errors";
+import type { LogEntry, LogLevel } from "../../types/app";
+
+const LEVELS: LogLevel[] = ["error", "warn", "info", "debug", "trace"];
+
+export default function LogsSection() {
+    const { config, updateLogLevel } = useAppStore();
+    const [filter, setFilter] = useState<LogLevel>("warn");
+    const [entries, setEntries] = useState<LogEntry[]>([]);
+    const [error, setError] = useState<string | null>(null);
+
+    const loadLogs = async () => {
+        setError(null);
+        try {
+            setEntries(await getRecentLogs(filter, 100));
+        } catch (e) {
+            setError(errorMessage(e));
+        }
+    };
+
+    useEffect(() => {
+        loadLogs();
+    }, [filter]);
+
+    if (!config) return null;
+
+    return (
+        <section className="settings-group">
+            <h3 className="group-title">Logs</h3>
+            <div className="group-card">
+                <div className="setting-item">
+                    <div className="setting-label">
+                        <span>Log Level</span>
+                        <small>Least severe level written to disk</small>
+                    </div>
+                    <div className="setting-control">
+                        <select
+                            value={config.log_level ?? "info"}
+                            onChange={(e) => updateLogLevel(e.target.value as LogLevel)}
+                        >
+                            {LEVELS.map((level) => (
+                                <option key={level} value={level}>{level}</option>
+                            ))}
+                        </select>
+                    </div>
+                </div>
+                <div className="setting-item column">
+                    <div style={{ display: "flex", gap: "8px", alignItems: "center" }}>
+                        <span style={{ fontSize: "12px", color: "var(--text-secondary)", flex: 1 }}>Recent entries</span>
+                        <select value={filter} onChange={(e) => setFilter(e.target.value as LogLevel)}>
+                            {LEVELS.map((level) => (
+                                <option key={level} value={level}>{level} and above</option>
+                            ))}
+                        </select>
+                        <button className="small-btn" onClick={loadLogs}>
+                            Refresh
+                        </button>
+                    </div>
+                    {error && <div style={{ fontSize: "12px", color: "var(--danger, #f87171)" }}>{error}</div>}
+                    <div style={{ maxHeight: "240px", overflowY: "auto", fontFamily: "monospace", fontSize: "11px" }}>
+                        {entries.length === 0 && !error && (
+                            <div style={{ color: "var(--text-secondary)" }}>No entries</div>
+                        )}
+                        {entries.map((entry, i) => (
+                            <div key={i} title={JSON.stringify(entry.fields)}>
+                                <span style={{ color: "var(--text-secondary)" }}>{entry.timestamp}</span>{" "}
+                                <strong>{entry.level.toUpperCase()}</strong> {entry.message}
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            </div>
+        </section>
+    );
+}
@@ -37,7 +37,7 @@ export default function OrganizationSection() {
                             type="text"
                             value={newCategory}
                             onChange={(e) => setNewCategory(e.target.value)}
-                            placeholder="New Category..."
+                            placeholder="New Category (Parent/Child)..."
                         />
                         <button className="small-btn" onClick={handleAdd}>
                             Add
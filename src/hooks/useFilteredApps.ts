@@ -1,5 +1,6 @@
 import { useMemo } from "react";
 import type { AppInfo } from "../types/app";
+import { appInCategory } from "../lib/categories";
 
 export default function useFilteredApps({
   apps,
@@ -32,7 +33,7 @@ export default function useFilteredApps({
         .sort((a, b) => b.usage_count - a.usage_count)
         .slice(0, 10);
     } else if (selectedCategory) {
-      result = result.filter((app) => !app.is_system && !app.is_script && app.category === selectedCategory);
+      result = result.filter((app) => !app.is_system && !app.is_script && appInCategory(app, selectedCategory));
     }
 
     if (sortBy === "name") {
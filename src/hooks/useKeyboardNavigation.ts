@@ -17,6 +17,8 @@ export default function useKeyboardNavigation({
   searchQuery,
   setSearchQuery,
   onLaunch,
+  onUndo,
+  onRedo,
 }: {
   filteredApps: AppInfo[];
   selectedIndex: number;
@@ -33,9 +35,20 @@ export default function useKeyboardNavigation({
   searchQuery: string;
   setSearchQuery: React.Dispatch<React.SetStateAction<string>>;
   onLaunch: (path: string) => void;
+  onUndo: () => void;
+  onRedo: () => void;
 }) {
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
+      const target = e.target as HTMLElement | null;
+      const isEditing = target?.tagName === "INPUT" || target?.tagName === "TEXTAREA";
+      if (e.metaKey && e.key.toLowerCase() === "z" && !isEditing) {
+        e.preventDefault();
+        if (e.shiftKey) onRedo();
+        else onUndo();
+        return;
+      }
+
       if (e.key === "Escape") {
         if (quickLookApp) setQuickLookApp(null);
         else if (viewMode === "settings") setViewMode("grid");
@@ -110,5 +123,7 @@ export default function useKeyboardNavigation({
     setSelectedSideIndex,
     setViewMode,
     onLaunch,
+    onUndo,
+    onRedo,
   ]);
 }
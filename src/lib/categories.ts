@@ -0,0 +1,52 @@
+import type { AppInfo, CategoryNode } from "../types/app";
+
+/** Every category path in the tree, parents before their children. */
+export function flattenCategoryTree(nodes: CategoryNode[], prefix = ""): string[] {
+  return nodes.flatMap((node) => {
+    const path = prefix ? `${prefix}/${node.name}` : node.name;
+    return [path, ...flattenCategoryTree(node.children ?? [], path)];
+  });
+}
+
+/** Rebuilds a tree from paths listed parents-first, e.g. after a reorder. */
+export function buildCategoryTree(paths: string[]): CategoryNode[] {
+  const roots: CategoryNode[] = [];
+  for (const path of paths) {
+    let siblings = roots;
+    for (const name of path.split("/")) {
+      let node = siblings.find((n) => n.name === name);
+      if (!node) {
+        node = { name };
+        siblings.push(node);
+      }
+      node.children = node.children ?? [];
+      siblings = node.children;
+    }
+  }
+  const prune = (nodes: CategoryNode[]) =>
+    nodes.forEach((n) => {
+      if (n.children && n.children.length === 0) delete n.children;
+      else if (n.children) prune(n.children);
+    });
+  prune(roots);
+  return roots;
+}
+
+/** Whether `category` is `ancestor` or nested anywhere below it. */
+export function isInCategory(category: string | null | undefined, ancestor: string): boolean {
+  return !!category && (category === ancestor || category.startsWith(`${ancestor}/`));
+}
+
+export function categoryDepth(path: string): number {
+  return path.split("/").length - 1;
+}
+
+export function categoryLabel(path: string): string {
+  return path.slice(path.lastIndexOf("/") + 1);
+}
+
+/** Whether any of the app's tags (or its category) is in `category`'s view. */
+export function appInCategory(app: AppInfo, category: string): boolean {
+  const tags = app.tags && app.tags.length > 0 ? app.tags : [app.category];
+  return tags.some((tag) => isInCategory(tag, category));
+}
@@ -6,10 +6,11 @@ export function buildAppContextMenuItems(params: {
   allCategories: string[];
   onLaunch: (path: string) => void;
   onSetCategory: (path: string, category: string) => void;
+  onToggleTag: (path: string, tag: string) => void;
   onRevealInFinder: (path: string) => void;
   onCopyText: (text: string) => void;
 }): ContextMenuItem[] {
-  const { app, allCategories, onLaunch, onSetCategory, onRevealInFinder, onCopyText } = params;
+  const { app, allCategories, onLaunch, onSetCategory, onToggleTag, onRevealInFinder, onCopyText } = params;
   const items: ContextMenuItem[] = [];
   if (!app) return items;
 
@@ -40,6 +41,17 @@ export function buildAppContextMenuItems(params: {
         disabled: app.category === c,
       });
     }
+
+    items.push({ type: "divider" });
+    items.push({ type: "header", label: "Tags" });
+    for (const c of customCategories) {
+      items.push({
+        type: "item",
+        label: c,
+        onClick: () => onToggleTag(app.path, c),
+        checked: !!app.tags?.includes(c),
+      });
+    }
   }
 
   items.push({ type: "divider" });
@@ -0,0 +1,12 @@
+import type { AppError } from "../types/app";
+
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === "object" && e !== null && "code" in e && "message" in e;
+}
+
+export function errorMessage(e: unknown): string {
+  if (typeof e === "string") return e;
+  if (isAppError(e)) return e.message;
+  if (e instanceof Error) return e.message;
+  return "未知错误";
+}
@@ -1,20 +1,30 @@
 import { create } from 'zustand';
 import { 
   getConfig, 
-  saveConfig, 
+  patchConfig as tauriPatchConfig,
+  takeConfigLoadError,
   getInstalledApps, 
   updateAppCategory,
+  addAppTag,
+  removeAppTag,
   addCategory as tauriAddCategory,
   removeCategory as tauriRemoveCategory,
+  renameCategory as tauriRenameCategory,
+  mergeCategories as tauriMergeCategories,
   updateShortcut as tauriUpdateShortcut,
   addScript as tauriAddScript,
   removeScript as tauriRemoveScript,
   updateScript as tauriUpdateScript,
   launchApp as tauriLaunchApp,
-  runScript as tauriRunScript
+  runScript as tauriRunScript,
+  undo as tauriUndo,
+  redo as tauriRedo,
+  clearUsageData as tauriClearUsageData
 } from '../api/tauri';
-import type { AppConfig, AppInfo } from '../types/app';
+import type { AppConfig, AppInfo, ConfigLoadError, FieldError, LogLevel, UsageScope } from '../types/app';
 import { mergeScriptsIntoApps } from '../lib/apps';
+import { buildCategoryTree } from '../lib/categories';
+import { errorMessage, isAppError } from '../lib/errors';
 
 interface AppState {
   apps: AppInfo[];
@@ -26,10 +36,15 @@ interface AppState {
   loadInitialData: () => Promise<void>;
   loadApps: (refresh?: boolean) => Promise<void>;
   setNotice: (notice: { kind: 'error' | 'info'; message: string; key: string } | null) => void;
+  reportConfigLoadError: (error: ConfigLoadError) => void;
+  applyExternalConfig: (config: AppConfig) => Promise<void>;
+  refreshRevision: () => Promise<void>;
+  patchConfig: (patch: Partial<AppConfig>) => Promise<void>;
   
   // App Actions
   launchApp: (path: string) => Promise<void>;
   setAppCategory: (path: string, category: string) => Promise<void>;
+  toggleAppTag: (path: string, tag: string) => Promise<void>;
   
   // Config Actions
   updateTheme: (theme: string) => Promise<void>;
@@ -38,10 +53,19 @@ interface AppState {
   updateWallpaperFit: (fit: string) => Promise<void>;
   updateWallpaperPosition: (pos: string) => Promise<void>;
   updateShortcut: (shortcut: string) => Promise<void>;
+  updateIncognito: (incognito: boolean) => Promise<void>;
+  updateUsageRetention: (days: number | null) => Promise<void>;
+  updateLocale: (locale: NonNullable<AppConfig['locale']>) => Promise<void>;
+  updateLogLevel: (level: LogLevel) => Promise<void>;
+  
+  // Privacy Actions
+  clearUsageData: (scope: UsageScope) => Promise<void>;
   
   // Category Actions
   addCategory: (category: string) => Promise<void>;
   removeCategory: (category: string) => Promise<void>;
+  renameCategory: (from: string, to: string) => Promise<void>;
+  mergeCategories: (from: string, into: string) => Promise<void>;
   reorderCategories: (newOrder: string[]) => Promise<void>;
   
   // Script Actions
@@ -49,6 +73,10 @@ interface AppState {
   removeScript: (name: string) => Promise<void>;
   updateScript: (originalName: string, name: string, command: string, cwd?: string) => Promise<void>;
   runScript: (command: string, cwd?: string) => Promise<void>;
+
+  // History Actions
+  undo: () => Promise<void>;
+  redo: () => Promise<void>;
 }
 
 export const useAppStore = create<AppState>((set, get) => ({
@@ -59,10 +87,52 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   setNotice: (notice) => set({ notice }),
 
+  reportConfigLoadError: (error: ConfigLoadError) => {
+    set({
+      notice: {
+        kind: 'error',
+        message: error.backup_path
+          ? `配置文件解析失败（第 ${error.line} 行第 ${error.column} 列），已将原文件备份到 ${error.backup_path} 并尽量恢复设置`
+          : `配置文件解析失败（第 ${error.line} 行第 ${error.column} 列），原文件未能备份，已尽量恢复设置`,
+        key: 'config-load-error',
+      },
+    });
+  },
+
+  applyExternalConfig: async (config: AppConfig) => {
+    set({ config });
+    await get().loadApps();
+  },
+
+  // Backend commands bump `revision` on every write; keep ours current so
+  // the next save isn't rejected as stale. Local edits in flight are kept.
+  refreshRevision: async () => {
+    const { revision } = await getConfig();
+    const current = get().config;
+    if (current) set({ config: { ...current, revision } });
+  },
+
+  patchConfig: async (patch: Partial<AppConfig>) => {
+    try {
+      const saved = await tauriPatchConfig(patch);
+      const current = get().config;
+      if (current) set({ config: { ...current, revision: saved.revision } });
+    } catch (e) {
+      const errors = isAppError(e) && e.code === 'invalid_fields' ? (e.context.errors as FieldError[]) : [];
+      const fields = errors.map(err => err.field).join(', ');
+      set({
+        config: await getConfig(),
+        notice: { kind: 'error', message: `保存设置失败${fields ? `：${fields}` : ''}`, key: 'config-patch-error' },
+      });
+    }
+  },
+
   loadInitialData: async () => {
     try {
       const config = await getConfig();
       set({ config });
+      const loadError = await takeConfigLoadError();
+      if (loadError) get().reportConfigLoadError(loadError);
       await get().loadApps();
     } catch (e) {
       set({ notice: { kind: 'error', message: '加载配置失败', key: 'load-config-error' } });
@@ -91,7 +161,11 @@ export const useAppStore = create<AppState>((set, get) => ({
     const app = apps.find(a => a.path === path);
     
     if (app?.is_script && app.command) {
-      await tauriRunScript(app.command, app.cwd);
+      try {
+        await tauriRunScript(app.command, app.cwd);
+      } catch (e) {
+        set({ notice: { kind: 'error', message: `运行脚本失败：${errorMessage(e)}`, key: `script-error-${path}` } });
+      }
       return;
     }
 
@@ -101,8 +175,9 @@ export const useAppStore = create<AppState>((set, get) => ({
       set({
         apps: apps.map(a => a.path === path ? { ...a, usage_count: (a.usage_count || 0) + 1 } : a)
       });
+      await get().refreshRevision();
     } catch (e) {
-      set({ notice: { kind: 'error', message: '启动失败', key: `launch-error-${path}` } });
+      set({ notice: { kind: 'error', message: `启动失败：${errorMessage(e)}`, key: `launch-error-${path}` } });
       await get().loadApps(); // Reload to sync state
     }
   },
@@ -110,29 +185,41 @@ export const useAppStore = create<AppState>((set, get) => ({
   setAppCategory: async (path: string, category: string) => {
     try {
       await updateAppCategory(path, category);
-      set({
-        apps: get().apps.map(a => a.path === path ? { ...a, category } : a)
-      });
+      // Tags and newly created parent categories change too.
+      set({ config: await getConfig() });
+      await get().loadApps();
     } catch (e) {
       set({ notice: { kind: 'error', message: '更新分类失败', key: 'update-category-error' } });
     }
   },
 
+  toggleAppTag: async (path: string, tag: string) => {
+    const app = get().apps.find(a => a.path === path);
+    try {
+      if (app?.tags?.includes(tag)) await removeAppTag(path, tag);
+      else await addAppTag(path, tag);
+      set({ config: await getConfig() });
+      await get().loadApps();
+    } catch (e) {
+      set({ notice: { kind: 'error', message: '更新标签失败', key: 'update-tag-error' } });
+    }
+  },
+
   updateTheme: async (theme: string) => {
     const { config } = get();
     if (!config) return;
-    const nextConfig = { ...config, theme };
-    set({ config: nextConfig });
+    const patch = { theme };
+    set({ config: { ...config, ...patch } });
     document.documentElement.setAttribute('data-theme', theme);
-    await saveConfig(nextConfig);
+    await get().patchConfig(patch);
   },
 
   updateWallpaper: async (path: string) => {
     const { config } = get();
     if (!config) return;
-    const nextConfig = { ...config, wallpaper: path || null };
-    set({ config: nextConfig });
-    await saveConfig(nextConfig);
+    const patch = { wallpaper: path || null };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
   },
 
   updateWallpaperBlur: async (blur: number) => {
@@ -140,25 +227,69 @@ export const useAppStore = create<AppState>((set, get) => ({
     if (!config) return;
     const clampedBlur = Math.max(0, Math.min(20, blur));
     const overlay = (clampedBlur / 20) * 0.45;
-    const nextConfig = { ...config, wallpaper_blur: clampedBlur, wallpaper_overlay: overlay };
-    set({ config: nextConfig });
-    await saveConfig(nextConfig);
+    const patch = { wallpaper_blur: clampedBlur, wallpaper_overlay: overlay };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
   },
 
   updateWallpaperFit: async (fit: string) => {
     const { config } = get();
     if (!config) return;
-    const nextConfig = { ...config, wallpaper_fit: fit };
-    set({ config: nextConfig });
-    await saveConfig(nextConfig);
+    const patch = { wallpaper_fit: fit };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
   },
 
   updateWallpaperPosition: async (pos: string) => {
     const { config } = get();
     if (!config) return;
-    const nextConfig = { ...config, wallpaper_position: pos };
-    set({ config: nextConfig });
-    await saveConfig(nextConfig);
+    const patch = { wallpaper_position: pos };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
+  },
+
+  updateIncognito: async (incognito: boolean) => {
+    const { config } = get();
+    if (!config) return;
+    const patch = { incognito };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
+  },
+
+  updateUsageRetention: async (days: number | null) => {
+    const { config } = get();
+    if (!config) return;
+    const patch = { usage_retention_days: days };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
+    await get().loadApps();
+  },
+
+  updateLocale: async (locale: NonNullable<AppConfig['locale']>) => {
+    const { config } = get();
+    if (!config) return;
+    const patch = { locale };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
+  },
+
+  updateLogLevel: async (level: LogLevel) => {
+    const { config } = get();
+    if (!config) return;
+    const patch = { log_level: level };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
+  },
+
+  clearUsageData: async (scope: UsageScope) => {
+    try {
+      await tauriClearUsageData(scope);
+      set({ notice: { kind: 'info', message: '已清除使用记录', key: 'clear-usage' } });
+    } catch (e) {
+      set({ notice: { kind: 'error', message: `清除使用记录失败：${errorMessage(e)}`, key: 'clear-usage' } });
+    }
+    set({ config: await getConfig() });
+    await get().loadApps();
   },
 
   updateShortcut: async (shortcut: string) => {
@@ -179,12 +310,32 @@ export const useAppStore = create<AppState>((set, get) => ({
     set({ config });
   },
 
+  renameCategory: async (from: string, to: string) => {
+    try {
+      await tauriRenameCategory(from, to);
+    } catch (e) {
+      set({ notice: { kind: 'error', message: `重命名分类失败：${errorMessage(e)}`, key: 'rename-category-error' } });
+    }
+    set({ config: await getConfig() });
+    await get().loadApps();
+  },
+
+  mergeCategories: async (from: string, into: string) => {
+    try {
+      await tauriMergeCategories(from, into);
+    } catch (e) {
+      set({ notice: { kind: 'error', message: `合并分类失败：${errorMessage(e)}`, key: 'merge-category-error' } });
+    }
+    set({ config: await getConfig() });
+    await get().loadApps();
+  },
+
   reorderCategories: async (newOrder: string[]) => {
     const { config } = get();
     if (!config) return;
-    const nextConfig = { ...config, category_order: newOrder };
-    set({ config: nextConfig });
-    await saveConfig(nextConfig);
+    const patch = { category_order: buildCategoryTree(newOrder) };
+    set({ config: { ...config, ...patch } });
+    await get().patchConfig(patch);
   },
 
   addScript: async (name: string, command: string, cwd?: string) => {
@@ -204,5 +355,25 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   runScript: async (command: string, cwd?: string) => {
     await tauriRunScript(command, cwd);
-  }
+  },
+
+  undo: async () => {
+    try {
+      const label = await tauriUndo();
+      set({ config: await getConfig(), notice: { kind: 'info', message: `已撤销：${label}`, key: 'undo' } });
+      await get().loadApps();
+    } catch (e) {
+      set({ notice: { kind: 'info', message: '没有可撤销的操作', key: 'undo' } });
+    }
+  },
+
+  redo: async () => {
+    try {
+      const label = await tauriRedo();
+      set({ config: await getConfig(), notice: { kind: 'info', message: `已重做：${label}`, key: 'redo' } });
+      await get().loadApps();
+    } catch (e) {
+      set({ notice: { kind: 'info', message: '没有可重做的操作', key: 'redo' } });
+    }
+  },
 }));
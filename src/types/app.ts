@@ -3,9 +3,14 @@ export interface AppInfo {
   path: string;
   is_system: boolean;
   category?: string;
+  tags?: string[];
   usage_count: number;
   icon_data?: string;
   date_modified: number;
+  bundle_id?: string | null;
+  app_category_type?: string | null;
+  architectures?: string[];
+  date_installed?: number;
   is_script?: boolean;
   command?: string;
   cwd?: string;
@@ -18,18 +23,30 @@ export interface ScriptAction {
 }
 
 export interface AppConfig {
+  schema_version: number;
+  revision: number;
   categories: Record<string, string>;
   usage_counts: Record<string, number>;
   user_categories: string[];
   shortcut: string;
   scripts: ScriptAction[];
-  category_order: string[];
+  tags?: Record<string, string[]>;
+  category_order: CategoryNode[];
+  smart_categories?: SmartCategory[];
+  category_type_map?: Record<string, string>;
   theme: string;
   wallpaper?: string | null;
   wallpaper_blur: number;
   wallpaper_overlay: number;
   wallpaper_fit: string;
   wallpaper_position: string;
+  file_search_roots: string[];
+  file_search_ignore: string[];
+  sync_folder?: string | null;
+  incognito?: boolean;
+  usage_retention_days?: number | null;
+  locale?: 'system' | 'en' | 'zh-CN';
+  log_level?: LogLevel;
 }
 
 export interface WallpaperFile {
@@ -38,3 +55,184 @@ export interface WallpaperFile {
   size: number;
   modified_ms: number | null;
 }
+
+export type PluginPermission = "search" | "open_url" | "storage" | "filesystem";
+
+export interface PluginManifest {
+  id: string;
+  name: string;
+  version: string;
+  entry: string;
+  permissions: PluginPermission[];
+  filesystem: string[];
+  limits: { fuel: number; memory_bytes: number };
+}
+
+export interface PluginResult {
+  plugin_id: string;
+  id?: string | null;
+  title: string;
+  subtitle?: string | null;
+  url?: string | null;
+}
+
+export interface FileResult {
+  name: string;
+  path: string;
+  is_dir: boolean;
+}
+
+export type SystemAction =
+  | "lock_screen"
+  | "sleep"
+  | "restart"
+  | "shut_down"
+  | "log_out"
+  | "empty_trash"
+  | "toggle_dark_mode"
+  | "eject_all_disks"
+  | "show_desktop";
+
+export interface SystemActionInfo {
+  action: SystemAction;
+  title: string;
+  keywords: string[];
+  requires_confirmation: boolean;
+}
+
+export interface ConfigLoadError {
+  message: string;
+  line: number;
+  column: number;
+  backup_path?: string | null;
+}
+
+export interface ConfigBackup {
+  id: string;
+  path: string;
+  kind: "rolling" | "daily";
+  size: number;
+  modified_ms: number | null;
+}
+
+export interface ProfileInfo {
+  name: string;
+  active: boolean;
+}
+
+export type SyncStatus = "unchanged" | "pushed" | "pulled" | "merged";
+
+export interface SyncConflict {
+  field: string;
+  key: string | null;
+  local: unknown;
+  remote: unknown;
+}
+
+export interface SyncReport {
+  status: SyncStatus;
+  conflicts: SyncConflict[];
+}
+
+export interface FieldError {
+  field: string;
+  /** Stable identifier of the problem, e.g. "one_of". */
+  code: string;
+  message: string;
+}
+
+/** What every failing command rejects with; `code` is stable for matching. */
+export interface AppError {
+  code: string;
+  message: string;
+  context: Record<string, unknown>;
+}
+
+export interface HistoryState {
+  undo: string | null;
+  redo: string | null;
+}
+
+export interface CategoryNode {
+  name: string;
+  children?: CategoryNode[];
+}
+
+export type SmartRule =
+  | { kind: "bundle_id_prefix"; prefix: string }
+  | { kind: "name_regex"; pattern: string }
+  | { kind: "path_under"; root: string }
+  | { kind: "architecture"; arch: string }
+  | { kind: "app_category_type"; value: string }
+  | { kind: "installed_within"; days: number };
+
+export interface SmartCategory {
+  category: string;
+  rules: SmartRule[];
+  match_all?: boolean;
+}
+
+export type UsageGroupBy = "day" | "week" | "month";
+
+export interface UsageRange {
+  from: string;
+  to?: string;
+}
+
+export interface ItemUsage {
+  path: string;
+  name: string;
+  launches: number;
+}
+
+export interface AppUsage extends ItemUsage {
+  trend: number;
+}
+
+export interface PeriodUsage {
+  start: string;
+  launches: number;
+  change: number;
+  top: ItemUsage[];
+}
+
+export interface UsageStats {
+  from: string;
+  to: string;
+  launches: number;
+  apps: AppUsage[];
+  periods: PeriodUsage[];
+}
+
+export type UsageScope =
+  | { kind: "all" }
+  | { kind: "app"; path: string }
+  | { kind: "category"; category: string };
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export interface LogEntry {
+  timestamp: string;
+  level: LogLevel;
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+}
+
+export type CheckStatus = "ok" | "warning" | "error";
+
+export interface DiagnosticCheck {
+  id: string;
+  title: string;
+  status: CheckStatus;
+  detail: string;
+}
+
+export interface DiagnosticsReport {
+  generated_at: string;
+  app_version: string;
+  os: string;
+  checks: DiagnosticCheck[];
+  icon_cache_files: number;
+  icon_cache_bytes: number;
+}